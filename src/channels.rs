@@ -0,0 +1,311 @@
+//! Assignment of notes to MIDI channels.
+//!
+//! Pitch bend applies to a whole channel, so notes detuned by a fractional `<alter>` are moved
+//! to member channels of their own (in the spirit of MPE), shared only by notes that need
+//! the very same bend at the same time. Glissandi swept with pitch bend get a member channel
+//! to themselves.
+//!
+//! Member channels are lent to one part at a time and play with its program and controllers.
+
+use crate::{
+    midi::{Events, control_message, push},
+    timeline::{Control, ControlChange, TimelineEvent, TimelineNote, TimelinePart},
+};
+
+/// Pitch-bend sensitivity (in semitones) of member channels playing microtones.
+const BEND_RANGE: f64 = 2.0;
+
+/// The General MIDI percussion channel, never used for pitched notes.
+pub const PERCUSSION_CHANNEL: u8 = 9;
+
+/// A note played on a member channel.
+#[derive(Debug)]
+struct MemberNote {
+    start: usize,
+    end: usize,
+    /// Index of the part in `Timeline::parts`.
+    part: usize,
+    /// Index of the track the note is written to.
+    track: usize,
+    /// Pitch-bend sensitivity the note is played with.
+    range: u8,
+    /// The bend held over the note, `None` for a sweep.
+    bend: Option<i16>,
+    /// Every bend of the note, the first one at its start.
+    bends: Vec<(usize, midly::PitchBend)>,
+}
+
+#[derive(Debug)]
+struct Member {
+    channel: u8,
+    notes: Vec<MemberNote>,
+}
+
+impl Member {
+    fn accepts(&self, start: usize, end: usize, part: usize, bend: Option<i16>) -> bool {
+        self.notes
+            .iter()
+            .filter(|note| note.start < end && start < note.end)
+            .all(|note| bend.is_some() && note.bend == bend && note.part == part)
+    }
+}
+
+#[derive(Debug)]
+pub struct Channels {
+    /// The channels of each part, indexed like `ScorePartwise::part`: one for the whole part,
+    /// or one per voice.
+    mains: Vec<Vec<u8>>,
+    members: Vec<Member>,
+}
+
+impl Channels {
+    /// Percussion parts go to the percussion channel, every other part gets the `<midi-channel>`
+    /// it asks for, or the first one still free.
+    ///
+    /// With `voices` set, every voice of a pitched part after the first gets a free channel
+    /// of its own as well.
+    pub fn new(parts: &[TimelinePart], voices: bool) -> Self {
+        let mut mains: Vec<Vec<u8>> = Vec::with_capacity(parts.len());
+
+        for part in parts.iter() {
+            let taken = |mains: &[Vec<u8>], channel: &u8| {
+                *channel == PERCUSSION_CHANNEL || mains.iter().flatten().any(|c| c == channel)
+            };
+
+            let channel = if part.percussion {
+                PERCUSSION_CHANNEL
+            } else {
+                let requested = part.midi_channel.map(|channel| channel.saturating_sub(1));
+
+                requested
+                    .into_iter()
+                    .chain(0..16)
+                    .find(|channel| !taken(&mains, channel))
+                    // More parts than channels, start sharing
+                    .unwrap_or(mains.len() as u8 % PERCUSSION_CHANNEL)
+            };
+
+            mains.push(vec![channel]);
+
+            if !voices || part.percussion {
+                continue;
+            }
+
+            for _ in part.voices.iter().skip(1) {
+                // Voices share the part's channel once there are none left
+                let channel = (0..16).find(|channel| !taken(&mains, channel));
+                let part = mains.last_mut().unwrap();
+                part.push(channel.unwrap_or(part[0]));
+            }
+        }
+
+        let members = (0..16)
+            .filter(|channel| {
+                !mains.iter().flatten().any(|c| c == channel) && *channel != PERCUSSION_CHANNEL
+            })
+            .map(|channel| Member {
+                channel,
+                notes: Vec::new(),
+            })
+            .collect();
+
+        Self { mains, members }
+    }
+
+    /// The channel of the `id`th part.
+    pub fn main(&self, id: usize) -> u8 {
+        self.mains[id][0]
+    }
+
+    /// Every channel of the `id`th part.
+    pub fn part(&self, id: usize) -> &[u8] {
+        &self.mains[id]
+    }
+
+    /// The channel of the `voice`th voice of the `id`th part.
+    pub fn voice(&self, id: usize, voice: usize) -> u8 {
+        self.mains[id].get(voice).copied().unwrap_or(self.main(id))
+    }
+
+    /// Picks the channel for `note`, written to the `track`th track by a part playing on `main`.
+    ///
+    /// Falls back to the main channel, without any bend, once every member channel is taken.
+    pub fn assign(&mut self, track: usize, main: u8, note: &TimelineNote) -> u8 {
+        if note.detune == 0.0 && note.sweep.is_empty() {
+            return main;
+        }
+
+        let (range, bend) = if note.sweep.is_empty() {
+            let bend = midly::PitchBend::from_f64(note.detune / BEND_RANGE);
+            (BEND_RANGE, Some(bend.as_int()))
+        } else {
+            let widest = note
+                .sweep
+                .iter()
+                .fold(note.detune.abs(), |widest, (_, semitones)| {
+                    widest.max(semitones.abs())
+                });
+            (widest.ceil().max(1.0), None)
+        };
+
+        let Some(member) = self
+            .members
+            .iter_mut()
+            .find(|member| member.accepts(note.start, note.end(), note.id.part, bend))
+        else {
+            return main;
+        };
+
+        let bends = std::iter::once((note.start, note.detune))
+            .chain(note.sweep.iter().copied())
+            .map(|(position, semitones)| (position, midly::PitchBend::from_f64(semitones / range)))
+            .collect();
+
+        member.notes.push(MemberNote {
+            start: note.start,
+            end: note.end(),
+            part: note.id.part,
+            track,
+            range: range as u8,
+            bend,
+            bends,
+        });
+
+        member.channel
+    }
+
+    /// Writes the bends of the notes of the member channels, once every note is assigned, and
+    /// the `<sound>` changes of the parts they play.
+    ///
+    /// Each channel is set up in time order. Whenever it passes to another part, it takes the
+    /// program and controllers the part has at that time and follows the part's changes for
+    /// as long as it plays it. Its bend range changes only when the next note needs another one.
+    pub fn finish(mut self, tracks: &mut [Events<'_>], events: &[TimelineEvent]) {
+        let mut controls: Vec<&ControlChange> = events
+            .iter()
+            .filter_map(|event| match event {
+                TimelineEvent::Control(change) => Some(change),
+                TimelineEvent::Note(_) | TimelineEvent::Annotation(_) => None,
+            })
+            .collect();
+        controls.sort_by_key(|change| change.position);
+
+        // Set-ups go right ahead of the note-ons at the same tick, after the notes that end
+        let mut setups: Vec<Events> = vec![Events::new(); tracks.len()];
+
+        for member in self.members.iter_mut() {
+            member.notes.sort_by_key(|note| note.start);
+
+            let mut range = None;
+            let mut part = None;
+            for (index, note) in member.notes.iter().enumerate() {
+                let midi = |change: &ControlChange| midly::TrackEventKind::Midi {
+                    channel: member.channel.into(),
+                    message: control_message(change.control),
+                };
+
+                if part != Some(note.part) {
+                    part = Some(note.part);
+
+                    let end = member.notes[index..]
+                        .iter()
+                        .take_while(|next| next.part == note.part)
+                        .map(|next| next.end)
+                        .max()
+                        .unwrap_or(note.end);
+                    let changes = controls.iter().filter(|change| change.part == note.part);
+
+                    for change in state(changes.clone(), note.start) {
+                        push(&mut setups[note.track], note.start, midi(change));
+                    }
+
+                    let during = changes
+                        .filter(|change| note.start < change.position && change.position < end);
+                    for change in during {
+                        push(&mut tracks[note.track], change.position, midi(change));
+                    }
+                }
+
+                if range != Some(note.range) {
+                    range = Some(note.range);
+                    for kind in bend_range(member.channel, note.range) {
+                        push(&mut setups[note.track], note.start, kind);
+                    }
+                }
+
+                for (position, bend) in note.bends.iter().copied() {
+                    let kind = midly::TrackEventKind::Midi {
+                        channel: member.channel.into(),
+                        message: midly::MidiMessage::PitchBend { bend },
+                    };
+
+                    if position == note.start {
+                        push(&mut setups[note.track], position, kind);
+                    } else {
+                        push(&mut tracks[note.track], position, kind);
+                    }
+                }
+            }
+        }
+
+        for (events, setup) in tracks.iter_mut().zip(setups) {
+            for (position, setup) in setup {
+                let events = events.entry(position).or_default();
+                let first = events
+                    .iter()
+                    .position(|event| {
+                        matches!(
+                            event.kind,
+                            midly::TrackEventKind::Midi {
+                                message: midly::MidiMessage::NoteOn { .. },
+                                ..
+                            }
+                        )
+                    })
+                    .unwrap_or(events.len());
+
+                events.splice(first..first, setup);
+            }
+        }
+    }
+}
+
+/// The latest program and value of every controller set by `changes` up to `position`, in the
+/// order they were set.
+fn state<'a>(
+    changes: impl Iterator<Item = &'a &'a ControlChange>,
+    position: usize,
+) -> Vec<&'a ControlChange> {
+    let key = |control: Control| match control {
+        Control::Program(_) => None,
+        Control::Controller { controller, .. } => Some(controller),
+    };
+
+    let mut state: Vec<&ControlChange> = Vec::new();
+    for change in changes.filter(|change| change.position <= position) {
+        state.retain(|set| key(set.control) != key(change.control));
+        state.push(change);
+    }
+
+    state
+}
+
+/// Sets the pitch-bend sensitivity (RPN 0) of `channel`, followed by the null RPN.
+fn bend_range(channel: u8, semitones: u8) -> impl Iterator<Item = midly::TrackEventKind<'static>> {
+    [
+        (101, 0),
+        (100, 0),
+        (6, semitones),
+        (38, 0),
+        (101, 127),
+        (100, 127),
+    ]
+    .into_iter()
+    .map(move |(controller, value)| midly::TrackEventKind::Midi {
+        channel: channel.into(),
+        message: midly::MidiMessage::Controller {
+            controller: controller.into(),
+            value: value.into(),
+        },
+    })
+}
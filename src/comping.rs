@@ -0,0 +1,95 @@
+//! Block chords accompanying the chord symbols of a lead sheet.
+//!
+//! Each chord is held until the next chord symbol and struck again at every measure it lasts
+//! into, with its lowest note an octave below the rest.
+
+use crate::{
+    musicxml::Harmony,
+    timeline::{NoteId, TimelineEvent, TimelineNote},
+};
+
+/// Key of the C the chords are built on.
+const CHORD_OCTAVE: u8 = 48;
+const BASS_OCTAVE: u8 = 36;
+
+const VELOCITY: u8 = 80;
+
+/// The chord being played by the accompaniment.
+#[derive(Debug)]
+pub struct Comping {
+    /// Index of the accompaniment in `Timeline::parts`.
+    part: usize,
+    /// Keys of the current chord and the `<harmony>` it comes from.
+    chord: Option<(Vec<u8>, NoteId)>,
+    /// Where the current chord was last struck.
+    start: usize,
+}
+
+impl Comping {
+    pub fn new(part: usize) -> Self {
+        Self {
+            part,
+            chord: None,
+            start: 0,
+        }
+    }
+
+    pub fn part(&self) -> usize {
+        self.part
+    }
+
+    /// Ends the current chord and starts the one of `harmony`, `id` being where the
+    /// `<harmony>` is found.
+    pub fn harmony(
+        &mut self,
+        events: &mut Vec<TimelineEvent>,
+        position: usize,
+        harmony: &Harmony,
+        id: NoteId,
+    ) {
+        self.play(events, position);
+
+        self.chord = harmony.chord().map(|chord| {
+            let bass = BASS_OCTAVE + chord.bass.unwrap_or(chord.root);
+            let keys = chord
+                .intervals
+                .iter()
+                .map(|interval| CHORD_OCTAVE + chord.root + interval);
+
+            (std::iter::once(bass).chain(keys).collect(), id)
+        });
+        self.start = position;
+    }
+
+    /// Plays the current chord up to the end of the measure, it is struck again from there.
+    pub fn measure_end(&mut self, events: &mut Vec<TimelineEvent>, end: usize) {
+        self.play(events, end);
+        self.start = end;
+    }
+
+    fn play(&self, events: &mut Vec<TimelineEvent>, end: usize) {
+        let Some((keys, id)) = self.chord.as_ref() else {
+            return;
+        };
+
+        if end <= self.start {
+            return;
+        }
+
+        for key in keys.iter() {
+            events.push(TimelineEvent::Note(TimelineNote {
+                start: self.start,
+                duration: end - self.start,
+                key: *key,
+                detune: 0.0,
+                sweep: Vec::new(),
+                velocity: VELOCITY,
+                id: *id,
+                staff: 1,
+                voice: "1".to_string(),
+                fingering: Vec::new(),
+                lyrics: Vec::new(),
+            }));
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! Realization of figured bass: chords played above the bass line, as a continuo player would.
+//!
+//! Figures are read diatonically in the key signature, with common shorthands completed ("6"
+//! is a 6/3 chord, "7" a 7/5/3 and so on). The chord is played an octave above the intervals
+//! the figures name, notes without figures are left to the bass.
+
+use crate::{
+    TICKS_PER_QUARTER_NOTE_F64, measures,
+    musicxml::{Figure, FiguredBass, Note, Step},
+    timeline::{NoteId, TimelineEvent, TimelineNote},
+};
+
+const VELOCITY: u8 = 70;
+
+/// Figures waiting for the bass note they are written under.
+#[derive(Debug)]
+struct Pending<'a> {
+    figures: &'a [Figure],
+    /// Ticks until the next figures of the same note, the whole note when `None`.
+    duration: Option<usize>,
+    offset: isize,
+}
+
+/// Continuo realization of a part.
+#[derive(Debug)]
+pub struct Continuo<'a> {
+    /// Index of the realization in `Timeline::parts`.
+    part: usize,
+    fifths: i32,
+    pending: Vec<Pending<'a>>,
+    /// Intervals of the last chord, for figures that are only extended.
+    previous: Vec<Interval>,
+}
+
+/// An interval above the bass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Interval {
+    number: u8,
+    accidental: Accidental,
+}
+
+/// How a figure alters its note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Accidental {
+    /// As the key signature says.
+    Key,
+    /// Raised from a flat to a natural or from a natural to a sharp.
+    Sharp,
+    /// Lowered from a sharp to a natural or from a natural to a flat.
+    Flat,
+    Natural,
+    DoubleSharp,
+    DoubleFlat,
+}
+
+impl<'a> Continuo<'a> {
+    pub fn new(part: usize) -> Self {
+        Self {
+            part,
+            fifths: 0,
+            pending: Vec::new(),
+            previous: Vec::new(),
+        }
+    }
+
+    pub fn key(&mut self, fifths: &str) {
+        if let Ok(fifths) = fifths.trim().parse() {
+            self.fifths = fifths;
+        }
+    }
+
+    pub fn figured_bass(&mut self, figured_bass: &'a FiguredBass, divisions: f64) {
+        let offset = figured_bass.offset.as_ref().map_or(0, |offset| {
+            (offset.value / divisions * TICKS_PER_QUARTER_NOTE_F64).round() as isize
+        });
+
+        self.pending.push(Pending {
+            figures: &figured_bass.figure,
+            duration: figured_bass
+                .duration
+                .as_deref()
+                .map(|duration| measures::duration_ticks(duration, divisions)),
+            offset,
+        });
+    }
+
+    /// Plays the figures met since the previous note over `bass`, `played` being how the
+    /// note sounds.
+    pub fn note(&mut self, events: &mut Vec<TimelineEvent>, bass: &Note, played: &TimelineNote) {
+        let Some(pitch) = bass.pitch.as_ref() else {
+            return;
+        };
+
+        let written = crate::midi_note_number(pitch.step, pitch.octave, pitch.alter.unwrap_or(0.0));
+        let sounding = played.key as i32 - written as i32;
+
+        let mut start = played.start;
+        for pending in std::mem::take(&mut self.pending) {
+            let end = pending
+                .duration
+                .map_or(played.end(), |duration| start + duration)
+                .min(played.end());
+
+            let intervals = match intervals(pending.figures) {
+                Some(intervals) => intervals,
+                None => self.previous.clone(),
+            };
+
+            let on = start.saturating_add_signed(pending.offset);
+            if end > on {
+                for interval in intervals.iter() {
+                    let key = self.key_above(pitch.step, pitch.octave, *interval) + sounding + 12;
+                    let Ok(key) = u8::try_from(key) else {
+                        continue;
+                    };
+
+                    events.push(TimelineEvent::Note(TimelineNote {
+                        start: on,
+                        duration: end - on,
+                        key,
+                        detune: 0.0,
+                        sweep: Vec::new(),
+                        velocity: VELOCITY,
+                        id: NoteId {
+                            part: self.part,
+                            ..played.id
+                        },
+                        staff: 1,
+                        voice: "1".to_string(),
+                        fingering: Vec::new(),
+                        lyrics: Vec::new(),
+                    }));
+                }
+            }
+
+            self.previous = intervals;
+            start = end;
+        }
+    }
+
+    /// Written key of `interval` above the bass note `step` in `octave`.
+    fn key_above(&self, step: Step, octave: u8, interval: Interval) -> i32 {
+        let index = step.index() as usize + interval.number as usize - 1;
+        let step = Step::ALL[index % 7];
+        let octave = octave + (index / 7) as u8;
+
+        let signature = key_alter(self.fifths, step);
+        let alter = match interval.accidental {
+            Accidental::Key => signature,
+            Accidental::Sharp => (signature + 1.0).min(1.0),
+            Accidental::Flat => (signature - 1.0).max(-1.0),
+            Accidental::Natural => 0.0,
+            Accidental::DoubleSharp => 2.0,
+            Accidental::DoubleFlat => -2.0,
+        };
+
+        crate::midi_note_number(step, octave, alter) as i32
+    }
+}
+
+/// Alteration the key signature gives `step`.
+fn key_alter(fifths: i32, step: Step) -> f64 {
+    const SHARPS: [Step; 7] = [
+        Step::F,
+        Step::C,
+        Step::G,
+        Step::D,
+        Step::A,
+        Step::E,
+        Step::B,
+    ];
+
+    let position = SHARPS.iter().position(|sharp| *sharp == step).unwrap() as i32;
+
+    if fifths > position {
+        1.0
+    } else if fifths < 0 && 6 - position < -fifths {
+        -1.0
+    } else {
+        0.0
+    }
+}
+
+/// Accidental written by a prefix or suffix, a line through the number raises it.
+fn accidental(text: &str) -> Option<Accidental> {
+    match text.trim() {
+        "sharp" | "slash" | "back-slash" | "vertical" | "plus" => Some(Accidental::Sharp),
+        "flat" => Some(Accidental::Flat),
+        "natural" => Some(Accidental::Natural),
+        "double-sharp" | "sharp-sharp" => Some(Accidental::DoubleSharp),
+        "flat-flat" => Some(Accidental::DoubleFlat),
+        _ => None,
+    }
+}
+
+/// Intervals the figures stand for, `None` when they only extend the previous ones.
+fn intervals(figures: &[Figure]) -> Option<Vec<Interval>> {
+    let mut written: Vec<Interval> = Vec::new();
+    let mut extended = false;
+
+    for figure in figures {
+        let number = figure
+            .figure_number
+            .as_ref()
+            .and_then(|number| number.value.trim().parse::<u8>().ok())
+            .filter(|number| *number > 0);
+        let accidental = figure
+            .prefix
+            .iter()
+            .chain(figure.suffix.iter())
+            .find_map(|text| accidental(&text.value));
+
+        match (number, accidental) {
+            (Some(number), accidental) => written.push(Interval {
+                number,
+                accidental: accidental.unwrap_or(Accidental::Key),
+            }),
+            // A lone accidental alters the third
+            (None, Some(accidental)) => written.push(Interval {
+                number: 3,
+                accidental,
+            }),
+            (None, None) => extended |= figure.extend.is_some(),
+        }
+    }
+
+    if written.is_empty() && extended {
+        return None;
+    }
+
+    let mut numbers: Vec<u8> = written.iter().map(|interval| interval.number).collect();
+    numbers.sort_unstable_by(|a, b| b.cmp(a));
+
+    let implied: &[u8] = match numbers.as_slice() {
+        [] | [5] | [3] | [5, 3] => &[5, 3],
+        [6] | [6, 3] => &[6, 3],
+        [7] | [7, 3] | [7, 5] => &[7, 5, 3],
+        [6, 5] => &[6, 5, 3],
+        [4, 3] => &[6, 4, 3],
+        [2] | [4, 2] => &[6, 4, 2],
+        [4] | [5, 4] => &[5, 4],
+        [9] | [9, 3] | [9, 5] => &[9, 5, 3],
+        _ => &[],
+    };
+
+    let mut intervals = written;
+    for number in implied {
+        if !intervals.iter().any(|interval| interval.number == *number) {
+            intervals.push(Interval {
+                number: *number,
+                accidental: Accidental::Key,
+            });
+        }
+    }
+
+    Some(intervals)
+}
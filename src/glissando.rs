@@ -0,0 +1,225 @@
+//! Playback of `<glissando>` and `<slide>` notations.
+//!
+//! Instruments with fixed pitches (keyboards, harps, mallets) play a glissando as a fast run of
+//! notes, everything else sweeps a single note towards the target with pitch-bend messages.
+//! Lines wider than a synthesizer can bend are played as chromatic runs instead.
+
+use std::collections::HashMap;
+
+use crate::{
+    musicxml::{Note, NumberLevel, ScorePart, StartStop},
+    timeline::{TimelineEvent, TimelineNote},
+};
+
+/// Distance in ticks between two pitch-bend messages of a sweep.
+const BEND_INTERVAL: usize = 20;
+
+/// Largest pitch-bend sensitivity (in semitones) we ask the synthesizer for.
+const MAX_BEND_RANGE: u8 = 24;
+
+/// How a glissando is turned into MIDI events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Realization {
+    /// A run over every semitone between the two notes.
+    Chromatic,
+    /// A run over the white keys between the two notes.
+    Diatonic,
+    /// A single note bent up or down to the target, on a channel of its own.
+    PitchBend,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Line {
+    Glissando,
+    Slide,
+}
+
+#[derive(Debug)]
+struct Start {
+    /// The note the line starts from.
+    note: TimelineNote,
+    realization: Realization,
+}
+
+/// Glissandi of a single part that started but did not reach their target note yet.
+#[derive(Debug)]
+pub struct Glissandi {
+    fixed_pitch: bool,
+    pending: HashMap<(Line, NumberLevel), Start>,
+}
+
+impl Glissandi {
+    pub fn new(part: Option<&ScorePart>) -> Self {
+        Self {
+            fixed_pitch: part.is_none_or(has_fixed_pitches),
+            pending: HashMap::new(),
+        }
+    }
+
+    fn realization(&self, line: Line) -> Realization {
+        match (self.fixed_pitch, line) {
+            (true, Line::Glissando) => Realization::Diatonic,
+            (true, Line::Slide) => Realization::Chromatic,
+            (false, _) => Realization::PitchBend,
+        }
+    }
+
+    /// Returns `true` if the note starts a glissando, its events are then emitted once the
+    /// target note is known.
+    pub fn start(&mut self, note: &Note, start: TimelineNote) -> bool {
+        let mut started = false;
+
+        for (line, number) in lines(note, StartStop::Start) {
+            // Two lines starting from a single note can only be played once
+            if started {
+                continue;
+            }
+            started = true;
+
+            let realization = self.realization(line);
+            self.pending.insert(
+                (line, number),
+                Start {
+                    note: start.clone(),
+                    realization,
+                },
+            );
+        }
+
+        started
+    }
+
+    pub fn stop(&mut self, events: &mut Vec<TimelineEvent>, note: &Note, key: u8) {
+        for (line, number) in lines(note, StartStop::Stop) {
+            if let Some(start) = self.pending.remove(&(line, number)) {
+                realize(events, &start, key);
+            }
+        }
+    }
+
+    /// Plays glissandi that never reached their target as regular notes.
+    pub fn finish(self, events: &mut Vec<TimelineEvent>) {
+        for start in self.pending.into_values() {
+            realize(events, &start, start.note.key);
+        }
+    }
+}
+
+fn lines(note: &Note, kind: StartStop) -> impl Iterator<Item = (Line, NumberLevel)> + '_ {
+    note.notations.iter().flat_map(move |notations| {
+        let glissandi = notations
+            .glissando
+            .iter()
+            .filter(move |g| g.kind == kind)
+            .map(|g| (Line::Glissando, g.number.unwrap_or(1)));
+        let slides = notations
+            .slide
+            .iter()
+            .filter(move |s| s.kind == kind)
+            .map(|s| (Line::Slide, s.number.unwrap_or(1)));
+
+        glissandi.chain(slides)
+    })
+}
+
+/// Whether the part's instrument can only play discrete pitches, judged by its standard sound
+/// name first and its General MIDI program second.
+fn has_fixed_pitches(part: &ScorePart) -> bool {
+    let sound = part
+        .score_instrument
+        .iter()
+        .find_map(|instrument| instrument.instrument_sound.as_deref());
+
+    if let Some(sound) = sound {
+        return sound.starts_with("keyboard.")
+            || sound.starts_with("pitched-percussion.")
+            || sound.starts_with("pluck.harp");
+    }
+
+    let program = part
+        .midi_instrument
+        .iter()
+        .find_map(|instrument| instrument.midi_program);
+
+    match program {
+        // Pianos, chromatic percussion, organs and the orchestral harp
+        Some(program) => matches!(program, 1..=24 | 47),
+        None => true,
+    }
+}
+
+fn is_white_key(key: u8) -> bool {
+    matches!(key % 12, 0 | 2 | 4 | 5 | 7 | 9 | 11)
+}
+
+fn realize(events: &mut Vec<TimelineEvent>, start: &Start, target: u8) {
+    if start.note.key == target {
+        events.push(TimelineEvent::Note(start.note.clone()));
+        return;
+    }
+
+    match start.realization {
+        Realization::Chromatic => run(events, &start.note, target, false),
+        Realization::Diatonic => run(events, &start.note, target, true),
+        Realization::PitchBend => bend(events, &start.note, target),
+    }
+}
+
+fn run(events: &mut Vec<TimelineEvent>, start: &TimelineNote, target: u8, diatonic: bool) {
+    let keys: Vec<u8> = if start.key < target {
+        (start.key..target).collect()
+    } else {
+        (target + 1..=start.key).rev().collect()
+    };
+
+    let keys: Vec<u8> = keys
+        .into_iter()
+        .enumerate()
+        .filter(|(i, key)| !diatonic || *i == 0 || is_white_key(*key))
+        .map(|(_, key)| key)
+        .collect();
+
+    let steps = keys.len();
+    for (i, key) in keys.into_iter().enumerate() {
+        let on = start.start + start.duration * i / steps;
+        let off = start.start + start.duration * (i + 1) / steps;
+
+        let mut note = TimelineNote {
+            start: on,
+            duration: off - on,
+            key,
+            ..start.clone()
+        };
+
+        // Only the first note of the run is written in the score
+        if i > 0 {
+            note.fingering.clear();
+            note.lyrics.clear();
+        }
+
+        events.push(TimelineEvent::Note(note));
+    }
+}
+
+fn bend(events: &mut Vec<TimelineEvent>, start: &TimelineNote, target: u8) {
+    let interval = target as i32 - start.key as i32;
+    if interval.unsigned_abs() > MAX_BEND_RANGE as u32 {
+        run(events, start, target, false);
+        return;
+    }
+
+    let steps = (start.duration / BEND_INTERVAL).max(2);
+    let sweep = (1..steps)
+        .map(|step| {
+            let position = start.start + start.duration * step / steps;
+            let semitones = interval as f64 * step as f64 / (steps - 1) as f64;
+
+            (position, semitones)
+        })
+        .collect();
+
+    events.push(TimelineEvent::Note(TimelineNote {
+        sweep,
+        ..start.clone()
+    }));
+}
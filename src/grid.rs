@@ -0,0 +1,132 @@
+//! Bar lines and beat lines of a score, as played: after repeats and jumps (da capo, dal segno,
+//! to coda, fine) are taken and with the tempo applied.
+
+use crate::{
+    Options, TICKS_PER_QUARTER_NOTE_F64,
+    measures::MeasureMap,
+    musicxml::{ScorePartwise, Time},
+    repeats,
+    tempo::TempoMap,
+};
+
+/// A beat of a measure.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Beat {
+    pub tick: usize,
+    pub seconds: f64,
+    /// First beat of a group, like beats 1 and 4 of a 6/8 measure. Every beat of simple meters
+    /// is a primary one.
+    pub primary: bool,
+}
+
+/// A measure of the score as it is played, so repeated measures appear once per repeat.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridMeasure {
+    /// Index of the measure in `Part::measure`.
+    pub index: usize,
+    /// Number shown to musicians, `None` for pickups and other implicit measures.
+    pub number: Option<String>,
+    pub start: usize,
+    pub end: usize,
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+    pub time: Option<Time>,
+    pub beats: Vec<Beat>,
+}
+
+/// Beats of a measure of `time` grouped into primary beats, like 3+3 for 6/8 or 3+2 for
+/// "3+2"/8.
+fn beat_groups(time: &Time) -> Vec<usize> {
+    let groups: Option<Vec<usize>> = time
+        .beats
+        .split('+')
+        .map(|beats| beats.trim().parse().ok())
+        .collect();
+    let beat_type: usize = time.beat_type.trim().parse().unwrap_or(4);
+
+    match groups.as_deref() {
+        Some([beats]) if beat_type >= 8 && *beats > 3 && beats % 3 == 0 => vec![3; beats / 3],
+        Some([beats]) => vec![1; *beats],
+        Some(groups) => groups.to_vec(),
+        None => Vec::new(),
+    }
+}
+
+/// Every measure of the score in the order it is played, repeats and jumps taken, with its beats.
+///
+/// Measures are those of the first part. The beats of a pickup are counted back from its end,
+/// so they line up with the last beats of a full measure.
+pub fn measure_grid(score: &ScorePartwise, options: &Options) -> Vec<GridMeasure> {
+    let Some(part) = score.part.first() else {
+        return Vec::new();
+    };
+
+    let tempo_map = TempoMap::from_score(score, options);
+    let map = MeasureMap::new(part);
+
+    let mut grid = Vec::new();
+    let mut start = 0;
+
+    for index in repeats::playback_order(part) {
+        let Some(measure) = map.get(index) else {
+            continue;
+        };
+
+        let end = start + measure.length;
+
+        let beat = measure
+            .time
+            .as_ref()
+            .map_or(TICKS_PER_QUARTER_NOTE_F64, |time| {
+                let beat_type: f64 = time.beat_type.trim().parse().unwrap_or(4.0);
+                4.0 / beat_type * TICKS_PER_QUARTER_NOTE_F64
+            });
+        let groups = measure
+            .time
+            .as_ref()
+            .map(beat_groups)
+            .unwrap_or_else(|| vec![1; (measure.length as f64 / beat) as usize]);
+
+        let mut primary = Vec::new();
+        for group in groups {
+            primary.push(true);
+            primary.extend(std::iter::repeat_n(false, group.saturating_sub(1)));
+        }
+
+        let count = primary.len();
+        let full = (count as f64 * beat).round() as usize;
+        // Where the first beat of a full measure would be
+        let origin = if measure.pickup {
+            end as f64 - full as f64
+        } else {
+            start as f64
+        };
+
+        let beats = primary
+            .into_iter()
+            .enumerate()
+            .map(|(i, primary)| ((origin + i as f64 * beat).round(), primary))
+            .filter(|(tick, _)| *tick >= start as f64 && *tick < end as f64)
+            .map(|(tick, primary)| Beat {
+                tick: tick as usize,
+                seconds: tempo_map.seconds(tick as usize),
+                primary,
+            })
+            .collect();
+
+        grid.push(GridMeasure {
+            index,
+            number: measure.number.clone(),
+            start,
+            end,
+            start_seconds: tempo_map.seconds(start),
+            end_seconds: tempo_map.seconds(end),
+            time: measure.time.clone(),
+            beats,
+        });
+
+        start = end;
+    }
+
+    grid
+}
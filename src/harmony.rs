@@ -0,0 +1,332 @@
+//! Chord symbols: how they are named and which notes they are made of.
+
+use crate::musicxml::{DegreeTypeValue, Harmony, HarmonyAlter, HarmonyStep, KindValue};
+
+impl KindValue {
+    /// Semitones of the notes of the chord above its root, extensions above the octave.
+    pub fn intervals(self) -> &'static [u8] {
+        match self {
+            KindValue::Major | KindValue::Neapolitan => &[0, 4, 7],
+            KindValue::Minor => &[0, 3, 7],
+            KindValue::Augmented => &[0, 4, 8],
+            KindValue::Diminished => &[0, 3, 6],
+            KindValue::Dominant | KindValue::German => &[0, 4, 7, 10],
+            KindValue::MajorSeventh => &[0, 4, 7, 11],
+            KindValue::MinorSeventh => &[0, 3, 7, 10],
+            KindValue::DiminishedSeventh => &[0, 3, 6, 9],
+            KindValue::AugmentedSeventh => &[0, 4, 8, 10],
+            KindValue::HalfDiminished => &[0, 3, 6, 10],
+            KindValue::MajorMinor => &[0, 3, 7, 11],
+            KindValue::MajorSixth => &[0, 4, 7, 9],
+            KindValue::MinorSixth => &[0, 3, 7, 9],
+            KindValue::DominantNinth => &[0, 4, 7, 10, 14],
+            KindValue::MajorNinth => &[0, 4, 7, 11, 14],
+            KindValue::MinorNinth => &[0, 3, 7, 10, 14],
+            KindValue::Dominant11th => &[0, 4, 7, 10, 14, 17],
+            KindValue::Major11th => &[0, 4, 7, 11, 14, 17],
+            KindValue::Minor11th => &[0, 3, 7, 10, 14, 17],
+            KindValue::Dominant13th => &[0, 4, 7, 10, 14, 21],
+            KindValue::Major13th => &[0, 4, 7, 11, 14, 21],
+            KindValue::Minor13th => &[0, 3, 7, 10, 14, 21],
+            KindValue::SuspendedSecond => &[0, 2, 7],
+            KindValue::SuspendedFourth => &[0, 5, 7],
+            KindValue::Italian => &[0, 4, 10],
+            KindValue::French => &[0, 4, 6, 10],
+            KindValue::Pedal | KindValue::Other => &[0],
+            KindValue::Power => &[0, 7],
+            KindValue::Tristan => &[0, 6, 10, 15],
+            KindValue::None => &[],
+        }
+    }
+
+    /// What is written after the root when the score does not say.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            KindValue::Major | KindValue::Other => "",
+            KindValue::Minor => "m",
+            KindValue::Augmented => "+",
+            KindValue::Diminished => "dim",
+            KindValue::Dominant => "7",
+            KindValue::MajorSeventh => "maj7",
+            KindValue::MinorSeventh => "m7",
+            KindValue::DiminishedSeventh => "dim7",
+            KindValue::AugmentedSeventh => "+7",
+            KindValue::HalfDiminished => "m7b5",
+            KindValue::MajorMinor => "m(maj7)",
+            KindValue::MajorSixth => "6",
+            KindValue::MinorSixth => "m6",
+            KindValue::DominantNinth => "9",
+            KindValue::MajorNinth => "maj9",
+            KindValue::MinorNinth => "m9",
+            KindValue::Dominant11th => "11",
+            KindValue::Major11th => "maj11",
+            KindValue::Minor11th => "m11",
+            KindValue::Dominant13th => "13",
+            KindValue::Major13th => "maj13",
+            KindValue::Minor13th => "m13",
+            KindValue::SuspendedSecond => "sus2",
+            KindValue::SuspendedFourth => "sus4",
+            KindValue::Neapolitan => "N6",
+            KindValue::Italian => "It+6",
+            KindValue::French => "Fr+6",
+            KindValue::German => "Ger+6",
+            KindValue::Pedal => "ped",
+            KindValue::Power => "5",
+            KindValue::Tristan => "Tristan",
+            KindValue::None => "N.C.",
+        }
+    }
+
+    /// Chords with a minor third, written with a lowercase roman numeral.
+    fn is_minor(self) -> bool {
+        matches!(
+            self,
+            KindValue::Minor
+                | KindValue::MinorSeventh
+                | KindValue::MinorSixth
+                | KindValue::MinorNinth
+                | KindValue::Minor11th
+                | KindValue::Minor13th
+                | KindValue::MajorMinor
+                | KindValue::Diminished
+                | KindValue::DiminishedSeventh
+                | KindValue::HalfDiminished
+        )
+    }
+}
+
+/// Notes of a chord symbol, as pitch classes counting from C.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chord {
+    pub root: u8,
+    /// Semitones above the root in ascending order, extensions above the octave.
+    pub intervals: Vec<u8>,
+    /// Lowest note, when it is not the root.
+    pub bass: Option<u8>,
+}
+
+impl Harmony {
+    /// The chord symbol as it would be written: "Cmaj7", "F#m7b5", "G7/B", "Dm(add9)"...
+    ///
+    /// Roman numerals are written in uppercase for major chords and lowercase for minor ones,
+    /// functions as they are. The `text` attributes of the score are honored.
+    pub fn name(&self) -> String {
+        if self.kind.value == KindValue::None {
+            return self
+                .kind
+                .text
+                .clone()
+                .unwrap_or_else(|| KindValue::None.symbol().to_string());
+        }
+
+        let mut name = if let Some(root) = self.root.as_ref() {
+            step_name(&root.root_step, root.root_alter.as_ref())
+        } else if let Some(numeral) = self.numeral.as_ref() {
+            let roman = match &numeral.numeral_root.text {
+                Some(text) => text.clone(),
+                None => roman(numeral.numeral_root.value),
+            };
+            let roman = if self.kind.value.is_minor() {
+                roman.to_lowercase()
+            } else {
+                roman
+            };
+
+            let alter = numeral
+                .numeral_alter
+                .as_ref()
+                .map_or(0.0, |alter| alter.value);
+            format!("{}{roman}", accidentals(alter))
+        } else {
+            self.function
+                .as_ref()
+                .map(|function| function.value.trim().to_string())
+                .unwrap_or_default()
+        };
+
+        let symbol = self.kind.value.symbol();
+        match &self.kind.text {
+            Some(text) => name.push_str(text),
+            // A lowercase numeral already says the chord is minor
+            None if self.root.is_none() && self.numeral.is_some() && self.kind.value.is_minor() => {
+                name.push_str(symbol.strip_prefix('m').unwrap_or(symbol))
+            }
+            None => name.push_str(symbol),
+        }
+
+        for degree in self.degree.iter() {
+            if degree.print_object.as_deref() == Some("no") {
+                continue;
+            }
+
+            let kind = match (&degree.degree_type.text, degree.degree_type.value) {
+                (Some(text), _) => text.as_str(),
+                (None, DegreeTypeValue::Add) => "add",
+                (None, DegreeTypeValue::Alter) => "",
+                (None, DegreeTypeValue::Subtract) => "no",
+            };
+
+            let degree_name = format!(
+                "{kind}{}{}",
+                accidentals(degree.degree_alter.value),
+                degree.degree_value.value
+            );
+
+            // Added notes are set apart from the extensions of the chord
+            if degree.degree_type.text.is_none() && degree.degree_type.value == DegreeTypeValue::Add
+            {
+                name.push_str(&format!("({degree_name})"));
+            } else {
+                name.push_str(&degree_name);
+            }
+        }
+
+        if let Some(bass) = self.bass.as_ref() {
+            let separator = bass
+                .bass_separator
+                .as_ref()
+                .map_or("/", |separator| separator.value.as_str());
+
+            name.push_str(separator);
+            name.push_str(&step_name(&bass.bass_step, bass.bass_alter.as_ref()));
+        }
+
+        name
+    }
+
+    /// Notes of the chord, `None` for "N.C." and for numerals and functions, whose root
+    /// depends on the key.
+    ///
+    /// Degrees are added, altered or removed and an `<inversion>` without `<bass>` puts the
+    /// matching chord note in the bass.
+    pub fn chord(&self) -> Option<Chord> {
+        let root = self.root.as_ref()?;
+        if self.kind.value == KindValue::None {
+            return None;
+        }
+
+        let mut intervals: Vec<i16> = self
+            .kind
+            .value
+            .intervals()
+            .iter()
+            .map(|interval| *interval as i16)
+            .collect();
+
+        for degree in self.degree.iter() {
+            let Some(base) = degree_semitones(degree.degree_value.value) else {
+                continue;
+            };
+            let value = base + degree.degree_alter.value.round() as i16;
+            let number = degree.degree_value.value;
+
+            match degree.degree_type.value {
+                DegreeTypeValue::Add => intervals.push(value),
+                DegreeTypeValue::Alter => {
+                    for interval in intervals.iter_mut() {
+                        if same_degree(*interval, number) {
+                            *interval = value;
+                        }
+                    }
+                }
+                DegreeTypeValue::Subtract => {
+                    intervals.retain(|interval| !same_degree(*interval, number));
+                }
+            }
+        }
+
+        let mut intervals: Vec<u8> = intervals
+            .into_iter()
+            .filter_map(|interval| u8::try_from(interval).ok())
+            .collect();
+        intervals.sort_unstable();
+        intervals.dedup();
+
+        let root_class = pitch_class(&root.root_step, root.root_alter.as_ref());
+
+        let bass = match (self.bass.as_ref(), self.inversion.as_ref()) {
+            (Some(bass), _) => Some(pitch_class(&bass.bass_step, bass.bass_alter.as_ref())),
+            (None, Some(inversion)) if inversion.value > 0 => intervals
+                .get(inversion.value as usize)
+                .map(|interval| (root_class + interval) % 12),
+            _ => None,
+        };
+
+        Some(Chord {
+            root: root_class,
+            intervals,
+            bass: bass.filter(|bass| *bass != root_class),
+        })
+    }
+}
+
+fn step_name(step: &HarmonyStep, alter: Option<&HarmonyAlter>) -> String {
+    let letter = match &step.text {
+        Some(text) => text.clone(),
+        None => format!("{:?}", step.value),
+    };
+
+    // A printed alteration is written where the score asks, after the letter by default
+    match alter.filter(|alter| alter.print_object.as_deref() != Some("no")) {
+        Some(alter) if alter.location.as_deref() == Some("left") => {
+            format!("{}{letter}", accidentals(alter.value))
+        }
+        Some(alter) => format!("{letter}{}", accidentals(alter.value)),
+        None => letter,
+    }
+}
+
+fn pitch_class(step: &HarmonyStep, alter: Option<&HarmonyAlter>) -> u8 {
+    let alter = alter.map_or(0.0, |alter| alter.value).round() as i16;
+    (step.value.semitone() as i16 + alter).rem_euclid(12) as u8
+}
+
+fn accidentals(alter: f64) -> String {
+    let count = alter.abs().round() as usize;
+    if alter < 0.0 {
+        "b".repeat(count)
+    } else {
+        "#".repeat(count)
+    }
+}
+
+fn roman(degree: u8) -> String {
+    const NUMERALS: [&str; 7] = ["I", "II", "III", "IV", "V", "VI", "VII"];
+
+    match degree {
+        1..=7 => NUMERALS[degree as usize - 1].to_string(),
+        _ => degree.to_string(),
+    }
+}
+
+/// Semitones of a degree above the root of a dominant chord, so 7 is a minor seventh.
+fn degree_semitones(degree: u8) -> Option<i16> {
+    const SCALE: [i16; 7] = [0, 2, 4, 5, 7, 9, 10];
+
+    let degree = degree.checked_sub(1)? as i16;
+    Some(SCALE[(degree % 7) as usize] + degree / 7 * 12)
+}
+
+/// Whether a note `interval` semitones above the root is the `degree`th of the chord, whatever
+/// its alteration.
+fn same_degree(interval: i16, degree: u8) -> bool {
+    let of_interval = match interval {
+        0 => 1,
+        1..=2 => 2,
+        3..=4 => 3,
+        5 => 4,
+        6..=8 => 5,
+        9 => 6,
+        10..=11 => 7,
+        12 => 8,
+        13..=15 => 9,
+        16 => 10,
+        17..=18 => 11,
+        19 => 12,
+        20..=22 => 13,
+        _ => return false,
+    };
+
+    let octave = |degree: u8| (degree + 6) % 7;
+    octave(of_interval) == octave(degree)
+}
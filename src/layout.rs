@@ -0,0 +1,136 @@
+//! Page layout in millimeters, from the tenths of a staff space scores are laid out in, and
+//! where the engraver broke systems and pages.
+
+use crate::musicxml::{
+    Defaults, MarginType, MeasureItem, PageLayout, PageMargins, Part, Scaling, Tenths,
+};
+
+impl Scaling {
+    /// Length of `tenths` in millimeters.
+    pub fn millimeters(&self, tenths: Tenths) -> f64 {
+        tenths * self.millimeters / self.tenths
+    }
+
+    /// Length of `millimeters` in tenths.
+    pub fn tenths(&self, millimeters: f64) -> Tenths {
+        millimeters * self.tenths / self.millimeters
+    }
+}
+
+impl PageLayout {
+    /// Margins of the `page`th page, counting from 1. Margins without a type apply to every
+    /// page.
+    pub fn margins(&self, page: u32) -> Option<&PageMargins> {
+        let side = if page.is_multiple_of(2) {
+            MarginType::Even
+        } else {
+            MarginType::Odd
+        };
+
+        self.page_margins
+            .iter()
+            .find(|margins| margins.kind == Some(side))
+            .or_else(|| {
+                self.page_margins
+                    .iter()
+                    .find(|margins| matches!(margins.kind, None | Some(MarginType::Both)))
+            })
+    }
+}
+
+/// Size and margins of a page, in millimeters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Page {
+    pub width: f64,
+    pub height: f64,
+    pub left_margin: f64,
+    pub right_margin: f64,
+    pub top_margin: f64,
+    pub bottom_margin: f64,
+}
+
+impl Defaults {
+    /// Length of `tenths` in millimeters, `None` when the score has no `<scaling>`.
+    pub fn millimeters(&self, tenths: Tenths) -> Option<f64> {
+        self.scaling
+            .as_ref()
+            .map(|scaling| scaling.millimeters(tenths))
+    }
+
+    /// The `page`th page, counting from 1, `None` when the score does not give its size or
+    /// scaling. Missing margins are 0.
+    pub fn page(&self, page: u32) -> Option<Page> {
+        let scaling = self.scaling.as_ref()?;
+        let layout = self.page_layout.as_ref()?;
+        let margins = layout.margins(page);
+        let margin = |margin: fn(&PageMargins) -> Tenths| {
+            margins.map_or(0.0, |margins| scaling.millimeters(margin(margins)))
+        };
+
+        Some(Page {
+            width: scaling.millimeters(layout.page_width?),
+            height: scaling.millimeters(layout.page_height?),
+            left_margin: margin(|margins| margins.left_margin),
+            right_margin: margin(|margins| margins.right_margin),
+            top_margin: margin(|margins| margins.top_margin),
+            bottom_margin: margin(|margins| margins.bottom_margin),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakKind {
+    System,
+    Page,
+}
+
+/// A measure the engraver started a new system or page with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Break {
+    /// Index of the measure in `Part::measure`.
+    pub measure: usize,
+    /// The `number` attribute of the measure.
+    pub id: String,
+    pub kind: BreakKind,
+    /// Empty pages coming before the new page.
+    pub blank_pages: u32,
+    /// Number printed on the new page.
+    pub page_number: Option<String>,
+}
+
+/// System and page breaks of a part, from its `<print>` elements.
+///
+/// Every part of a score usually carries the same breaks, the first one is enough to lay out
+/// the pages.
+pub fn breaks(part: &Part) -> Vec<Break> {
+    let mut breaks = Vec::new();
+
+    for (index, measure) in part.measure.iter().enumerate() {
+        let print = measure.content.iter().find_map(|item| match item {
+            MeasureItem::Print(print) if print.is_new_system() => Some(print),
+            _ => None,
+        });
+
+        let Some(print) = print else {
+            continue;
+        };
+
+        // Blank pages and page numbers only come with a new page
+        let (kind, blank_pages, page_number) = if print.is_new_page() {
+            let blank_pages = print.blank_page.unwrap_or(0);
+            (BreakKind::Page, blank_pages, print.page_number.clone())
+        } else {
+            (BreakKind::System, 0, None)
+        };
+
+        breaks.push(Break {
+            measure: index,
+            id: measure.number.clone(),
+            kind,
+            blank_pages,
+            page_number,
+        });
+    }
+
+    breaks
+}
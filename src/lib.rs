@@ -0,0 +1,3098 @@
+pub mod grid;
+pub mod harmony;
+pub mod layout;
+pub mod measures;
+pub mod metadata;
+pub mod musicxml;
+pub mod repeats;
+pub mod staff;
+pub mod tempo;
+pub mod timeline;
+
+mod channels;
+mod comping;
+mod continuo;
+mod glissando;
+mod midi;
+mod percussion;
+mod playback;
+mod swing;
+mod transpose;
+
+use std::time::Duration;
+
+pub(crate) const TICKS_PER_QUARTER_NOTE: u16 = 480;
+const TICKS_PER_QUARTER_NOTE_F64: f64 = TICKS_PER_QUARTER_NOTE as f64;
+
+const MINUTE: Duration = Duration::from_secs(60);
+
+// 1s = 1_000_000µ
+// 1m = 60_000_000µ
+
+// BPM = 60_000_000 / MicrosecondsPerQuarterNote
+// BPM * MicrosecondsPerQuarterNote = 60_000_000
+// MicrosecondsPerQuarterNote = 60_000_000 / BPM
+
+/// Knobs of the MusicXML to MIDI conversion.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Derive the tempo from markings like "Allegro" when a direction carries
+    /// neither a `<sound tempo>` nor a metronome mark.
+    pub tempo_words: bool,
+    /// Write every staff of a multi-staff part (like the two hands of a piano part) to a track
+    /// of its own, instead of everything to a single track.
+    pub split_staves: bool,
+    pub voices: VoiceOutput,
+    /// Write the fingering of every note as a text event right ahead of its note-on.
+    pub fingering: bool,
+    /// Verse (`<lyric number>`) written as lyric events at the notes it is sung on.
+    pub verse: Option<String>,
+    /// Accompany the chord symbols with block chords on a part of their own.
+    pub comping: bool,
+    /// Realize the figured bass with chords above the bass line, on a part of their own.
+    pub continuo: bool,
+}
+
+/// Where the voices of a part are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VoiceOutput {
+    /// Together with the rest of the part.
+    #[default]
+    Merged,
+    /// To a track of their own.
+    Tracks,
+    /// To a channel of their own.
+    Channels,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            tempo_words: false,
+            split_staves: false,
+            voices: VoiceOutput::Merged,
+            fingering: false,
+            verse: None,
+            comping: false,
+            continuo: false,
+        }
+    }
+}
+
+/// Converts a MusicXML score to MIDI, with the texts of the file (track names, lyrics,
+/// markers...) kept in `arena`.
+pub fn parse<'a>(src: &str, arena: &'a midly::Arena) -> midly::Smf<'a> {
+    parse_with_options(src, &Options::default(), arena)
+}
+
+pub fn parse_with_options<'a>(
+    src: &str,
+    options: &Options,
+    arena: &'a midly::Arena,
+) -> midly::Smf<'a> {
+    let v: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+
+    let timeline = timeline::Timeline::new(&v, options);
+    let metadata = metadata::ScoreMetadata::new(&v);
+    midi::write(&timeline, &metadata, options, arena)
+}
+
+/// Moves a written MIDI key to the key that sounds on a transposing instrument.
+fn transposed_key(key: u8, transpose: &musicxml::Transpose) -> u8 {
+    (key as i32 + transpose.semitones()).clamp(0, 127) as u8
+}
+
+/// Returns the MIDI key of a pitched or unpitched note, along with its detune (see [`midi_pitch`]).
+fn note_key(note: &musicxml::Note, score_part: Option<&musicxml::ScorePart>) -> Option<(u8, f64)> {
+    if let Some(pitch) = note.pitch.as_ref() {
+        return Some(midi_pitch(pitch, note.accidental.as_ref()));
+    }
+
+    let unpitched = note.unpitched.as_ref()?;
+    Some((percussion::drum_key(note, unpitched, score_part), 0.0))
+}
+
+/// Returns the nearest MIDI key of a pitch and how many semitones the pitch is away from it.
+///
+/// Quarter-tone accidentals stand in for `<alter>` when the latter was rounded to whole semitones.
+fn midi_pitch(pitch: &musicxml::Pitch, accidental: Option<&musicxml::Accidental>) -> (u8, f64) {
+    let alter = pitch.alter.unwrap_or(0.0);
+    let alter = match accidental.and_then(|a| a.microtonal_alter()) {
+        Some(microtonal) if alter.fract() == 0.0 => microtonal,
+        _ => alter,
+    };
+
+    let key = midi_note_number(pitch.step, pitch.octave, alter);
+    (key, alter - alter.round())
+}
+
+fn midi_note_number(step: musicxml::Step, octave: u8, alter: f64) -> u8 {
+    let base = step.semitone();
+
+    // Microtones are rendered with pitch bend, see `midi_pitch`
+    let alter = alter.round() as i32;
+
+    (((octave + 1) * 12 + base) as i32 + alter) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! xml {
+        ( $($t:tt)* ) => {
+            stringify!($($t)*)
+        };
+    }
+
+    #[test]
+    fn test_name() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                    <key>
+                      <fifths>0</fifths>
+                    </key>
+                    <time>
+                      <beats>4</beats>
+                      <beat-type>4</beat-type>
+                    </time>
+                    <clef>
+                      <sign>G</sign>
+                      <line>2</line>
+                    </clef>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>G</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>A</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                  <note>
+                    <chord />
+                    <pitch>
+                      <step>D</step>
+                      <octave>5</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                  <note>
+                    <chord />
+                    <pitch>
+                      <step>F</step>
+                      <octave>5</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>G</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                  <note>
+                    <rest />
+                    <duration>1</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let arena = midly::Arena::new();
+        let midi = parse(src, &arena);
+        insta::assert_debug_snapshot!(midi);
+    }
+
+    #[test]
+    fn b() {
+        let src = xml!(
+        <score-partwise version="4.0">
+          <part-list>
+            <score-part id="P1">
+              <part-name>Piano</part-name>
+            </score-part>
+          </part-list>
+          <part id="P1">
+            <measure number="1" width="537.79">
+              <attributes>
+                <divisions>2</divisions>
+                <key>
+                  <fifths>0</fifths>
+                </key>
+                <time>
+                  <beats>4</beats>
+                  <beat-type>4</beat-type>
+                </time>
+                <staves>2</staves>
+                <clef number="1">
+                  <sign>G</sign>
+                  <line>2</line>
+                </clef>
+                <clef number="2">
+                  <sign>F</sign>
+                  <line>4</line>
+                </clef>
+              </attributes>
+              <note>
+                <pitch>
+                  <step>G</step>
+                  <octave>4</octave>
+                </pitch>
+                <duration>2</duration>
+                <staff>1</staff>
+              </note>
+              <note>
+                <pitch>
+                  <step>A</step>
+                  <octave>4</octave>
+                </pitch>
+                <duration>2</duration>
+                <staff>1</staff>
+              </note>
+              <note>
+                <chord />
+                <pitch>
+                  <step>C</step>
+                  <octave>5</octave>
+                </pitch>
+                <duration>2</duration>
+                <staff>1</staff>
+              </note>
+              <note>
+                <chord />
+                <pitch>
+                  <step>F</step>
+                  <octave>5</octave>
+                </pitch>
+                <duration>2</duration>
+                <staff>1</staff>
+              </note>
+              <note>
+                <pitch>
+                  <step>G</step>
+                  <octave>4</octave>
+                </pitch>
+                <duration>2</duration>
+                <staff>1</staff>
+              </note>
+              <note>
+                <rest />
+                <duration>2</duration>
+              </note>
+              <backup>
+                <duration>8</duration>
+              </backup>
+              <note>
+                <pitch>
+                  <step>G</step>
+                  <octave>2</octave>
+                </pitch>
+                <duration>2</duration>
+                <staff>2</staff>
+              </note>
+              <note>
+                <pitch>
+                  <step>B</step>
+                  <octave>2</octave>
+                </pitch>
+                <duration>1</duration>
+                <staff>2</staff>
+                <beam number="1">begin</beam>
+              </note>
+              <note>
+                <pitch>
+                  <step>C</step>
+                  <octave>3</octave>
+                </pitch>
+                <duration>1</duration>
+                <staff>2</staff>
+                <beam number="1">end</beam>
+              </note>
+              <note>
+                <pitch>
+                  <step>E</step>
+                  <octave>3</octave>
+                </pitch>
+                <duration>1</duration>
+                <staff>2</staff>
+              </note>
+              <note>
+                <rest />
+                <duration>1</duration>
+              </note>
+              <note>
+                <rest />
+                <duration>2</duration>
+              </note>
+            </measure>
+          </part>
+        </score-partwise>
+        );
+
+        let arena = midly::Arena::new();
+        let midi = parse(src, &arena);
+        insta::assert_debug_snapshot!(midi);
+    }
+
+    #[test]
+    fn glissando() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                  <score-instrument id="P1-I1">
+                    <instrument-name>Piano</instrument-name>
+                    <instrument-sound>keyboard.piano</instrument-sound>
+                  </score-instrument>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <notations>
+                      <glissando type="start" />
+                    </notations>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>G</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <notations>
+                      <glissando type="stop" />
+                    </notations>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let arena = midly::Arena::new();
+        let midi = parse(src, &arena);
+        insta::assert_debug_snapshot!(midi);
+    }
+
+    #[test]
+    fn slide() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Violin</part-name>
+                  <score-instrument id="P1-I1">
+                    <instrument-name>Violin</instrument-name>
+                    <instrument-sound>strings.violin</instrument-sound>
+                  </score-instrument>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <notations>
+                      <slide type="start" />
+                    </notations>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <notations>
+                      <slide type="stop" />
+                    </notations>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let arena = midly::Arena::new();
+        let midi = parse(src, &arena);
+        insta::assert_debug_snapshot!(midi);
+    }
+
+    #[test]
+    fn wide_slide() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Violin</part-name>
+                  <score-instrument id="P1-I1">
+                    <instrument-name>Violin</instrument-name>
+                    <instrument-sound>strings.violin</instrument-sound>
+                  </score-instrument>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>3</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <notations>
+                      <slide type="start" />
+                    </notations>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>5</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <notations>
+                      <slide type="stop" />
+                    </notations>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let timeline = timeline::Timeline::new(&score, &Options::default());
+
+        // Wider than the synthesizer bends, the slide is played as a chromatic run
+        let keys: Vec<(usize, u8, usize)> = timeline
+            .notes()
+            .map(|note| (note.start, note.key, note.sweep.len()))
+            .collect();
+        insta::assert_debug_snapshot!(keys);
+    }
+
+    #[test]
+    fn microtones() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Violin</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                  <note>
+                    <chord />
+                    <pitch>
+                      <step>E</step>
+                      <alter>-0.5</alter>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                  <note>
+                    <chord />
+                    <pitch>
+                      <step>G</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <accidental>quarter-sharp</accidental>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>F</step>
+                      <alter>1.5</alter>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let arena = midly::Arena::new();
+        let midi = parse(src, &arena);
+        insta::assert_debug_snapshot!(midi);
+    }
+
+    #[test]
+    fn microtone_sound() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Violin</part-name>
+                  <midi-instrument id="P1-I1">
+                    <midi-program>41</midi-program>
+                    <volume>80</volume>
+                  </midi-instrument>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <alter>0.5</alter>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>4</duration>
+                  </note>
+                  <backup>
+                    <duration>2</duration>
+                  </backup>
+                  <sound pan="-45" />
+                  <forward>
+                    <duration>2</duration>
+                  </forward>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        // The member channel plays the violin, and pans along with the part
+        let arena = midly::Arena::new();
+        let midi = parse(src, &arena);
+        insta::assert_debug_snapshot!(midi);
+    }
+
+    #[test]
+    fn percussion() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+                <score-part id="P2">
+                  <part-name>Drumset</part-name>
+                  <score-instrument id="P2-I37">
+                    <instrument-name>Bass Drum 1</instrument-name>
+                  </score-instrument>
+                  <score-instrument id="P2-I39">
+                    <instrument-name>Acoustic Snare</instrument-name>
+                  </score-instrument>
+                  <midi-instrument id="P2-I37">
+                    <midi-channel>10</midi-channel>
+                    <midi-unpitched>37</midi-unpitched>
+                  </midi-instrument>
+                  <midi-instrument id="P2-I39">
+                    <midi-channel>10</midi-channel>
+                    <midi-unpitched>39</midi-unpitched>
+                  </midi-instrument>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                </measure>
+              </part>
+              <part id="P2">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <note>
+                    <unpitched>
+                      <display-step>F</display-step>
+                      <display-octave>4</display-octave>
+                    </unpitched>
+                    <duration>1</duration>
+                    <instrument id="P2-I37" />
+                  </note>
+                  <note>
+                    <unpitched>
+                      <display-step>C</display-step>
+                      <display-octave>5</display-octave>
+                    </unpitched>
+                    <duration>1</duration>
+                    <instrument id="P2-I39" />
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let arena = midly::Arena::new();
+        let midi = parse(src, &arena);
+        insta::assert_debug_snapshot!(midi);
+    }
+
+    #[test]
+    fn transposing_instrument() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Clarinet</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                    <key>
+                      <fifths>2</fifths>
+                    </key>
+                    <transpose>
+                      <diatonic>-1</diatonic>
+                      <chromatic>-2</chromatic>
+                    </transpose>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>D</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>F</step>
+                      <alter>1</alter>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let arena = midly::Arena::new();
+        let midi = parse(src, &arena);
+        insta::assert_debug_snapshot!(midi);
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let score = score.concert_pitch();
+        insta::assert_debug_snapshot!(score.part[0].measure[0].content);
+    }
+
+    #[test]
+    fn transposed_staves() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Organ</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                    <staves>2</staves>
+                    <transpose number="1">
+                      <diatonic>-1</diatonic>
+                      <chromatic>-2</chromatic>
+                    </transpose>
+                    <transpose number="2">
+                      <diatonic>0</diatonic>
+                      <chromatic>0</chromatic>
+                      <octave-change>-1</octave-change>
+                    </transpose>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>D</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <staff>1</staff>
+                  </note>
+                  <note>
+                    <unpitched>
+                      <display-step>E</display-step>
+                      <display-octave>4</display-octave>
+                    </unpitched>
+                    <duration>1</duration>
+                    <staff>1</staff>
+                  </note>
+                  <backup>
+                    <duration>2</duration>
+                  </backup>
+                  <note>
+                    <pitch>
+                      <step>D</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>2</duration>
+                    <staff>2</staff>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let timeline = timeline::Timeline::new(&score, &Options::default());
+        let keys: Vec<u8> = timeline.notes().map(|note| note.key).collect();
+
+        let score = score.concert_pitch();
+        let notes: Vec<_> = score.part[0].measure[0]
+            .content
+            .iter()
+            .filter_map(|item| match item {
+                musicxml::MeasureItem::Note(note) => Some((&note.pitch, &note.unpitched)),
+                _ => None,
+            })
+            .collect();
+
+        insta::assert_debug_snapshot!((keys, notes));
+    }
+
+    #[test]
+    fn lower_staff_transpose() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Organ</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                    <key>
+                      <fifths>0</fifths>
+                    </key>
+                    <staves>2</staves>
+                    <transpose number="2">
+                      <diatonic>0</diatonic>
+                      <chromatic>0</chromatic>
+                      <octave-change>-1</octave-change>
+                    </transpose>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>5</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <staff>1</staff>
+                  </note>
+                  <backup>
+                    <duration>1</duration>
+                  </backup>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>3</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <staff>2</staff>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let keys = |score: &musicxml::ScorePartwise| -> Vec<u8> {
+            timeline::Timeline::new(score, &Options::default())
+                .notes()
+                .map(|note| note.key)
+                .collect()
+        };
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let concert = score.concert_pitch();
+
+        // The concert pitch view plays the same as the written score
+        assert_eq!(keys(&score), keys(&concert));
+        assert!(concert.defaults.unwrap().concert_score.is_some());
+        insta::assert_debug_snapshot!(concert.part[0].measure[0].content);
+    }
+
+    #[test]
+    fn staff_positions() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                    <staves>2</staves>
+                    <clef number="1">
+                      <sign>G</sign>
+                      <line>2</line>
+                      <clef-octave-change>-1</clef-octave-change>
+                    </clef>
+                    <clef number="2">
+                      <sign>F</sign>
+                      <line>4</line>
+                    </clef>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>3</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <staff>1</staff>
+                  </note>
+                  <direction>
+                    <direction-type>
+                      <octave-shift type="down" size="8" />
+                    </direction-type>
+                    <staff>1</staff>
+                  </direction>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>6</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <staff>1</staff>
+                  </note>
+                  <direction>
+                    <direction-type>
+                      <octave-shift type="stop" size="8" />
+                    </direction-type>
+                    <staff>1</staff>
+                  </direction>
+                  <backup>
+                    <duration>2</duration>
+                  </backup>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <staff>2</staff>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>A</step>
+                      <octave>3</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <staff>2</staff>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let positions: Vec<_> = staff::written_notes(&score.part[0])
+            .iter()
+            .map(|note| (note.staff, note.position.line(), note.position.space()))
+            .collect();
+
+        insta::assert_debug_snapshot!(positions);
+    }
+
+    #[test]
+    fn metronome() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <direction>
+                    <direction-type>
+                      <words>Allegro</words>
+                    </direction-type>
+                  </direction>
+                  <note>
+                    <rest />
+                    <duration>1</duration>
+                  </note>
+                  <direction>
+                    <direction-type>
+                      <metronome>
+                        <beat-unit>quarter</beat-unit>
+                        <beat-unit-dot />
+                        <per-minute>60</per-minute>
+                      </metronome>
+                    </direction-type>
+                  </direction>
+                  <note>
+                    <rest />
+                    <duration>1</duration>
+                  </note>
+                  <direction>
+                    <direction-type>
+                      <metronome>
+                        <beat-unit>quarter</beat-unit>
+                        <beat-unit>half</beat-unit>
+                      </metronome>
+                    </direction-type>
+                  </direction>
+                  <note>
+                    <rest />
+                    <duration>1</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let options = Options {
+            tempo_words: true,
+            ..Default::default()
+        };
+
+        let arena = midly::Arena::new();
+        let midi = parse_with_options(src, &options, &arena);
+        insta::assert_debug_snapshot!(midi);
+    }
+
+    #[test]
+    fn ritardando() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <direction>
+                    <direction-type>
+                      <metronome>
+                        <beat-unit>quarter</beat-unit>
+                        <per-minute>120</per-minute>
+                      </metronome>
+                    </direction-type>
+                  </direction>
+                  <note>
+                    <rest />
+                    <duration>1</duration>
+                  </note>
+                  <direction>
+                    <direction-type>
+                      <words>rit.</words>
+                    </direction-type>
+                    <direction-type>
+                      <dashes type="start" />
+                    </direction-type>
+                  </direction>
+                  <note>
+                    <rest />
+                    <duration>2</duration>
+                  </note>
+                  <direction>
+                    <direction-type>
+                      <dashes type="stop" />
+                    </direction-type>
+                  </direction>
+                  <direction>
+                    <direction-type>
+                      <metronome>
+                        <beat-unit>quarter</beat-unit>
+                        <per-minute>60</per-minute>
+                      </metronome>
+                    </direction-type>
+                  </direction>
+                  <note>
+                    <rest />
+                    <duration>1</duration>
+                  </note>
+                  <direction>
+                    <direction-type>
+                      <words>a tempo</words>
+                    </direction-type>
+                  </direction>
+                  <note>
+                    <rest />
+                    <duration>1</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let arena = midly::Arena::new();
+        let midi = parse(src, &arena);
+
+        let mut position = 0;
+        let tempi: Vec<_> = midi.tracks[0]
+            .iter()
+            .filter_map(|event| {
+                position += event.delta.as_int();
+                match event.kind {
+                    midly::TrackEventKind::Meta(midly::MetaMessage::Tempo(tempo)) => {
+                        Some((position, 60_000_000 / tempo.as_int()))
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
+
+        insta::assert_debug_snapshot!(tempi);
+    }
+
+    #[test]
+    fn swing() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>2</divisions>
+                  </attributes>
+                  <direction>
+                    <direction-type>
+                      <words>Swing</words>
+                    </direction-type>
+                    <sound>
+                      <swing>
+                        <first>2</first>
+                        <second>1</second>
+                        <swing-type>eighth</swing-type>
+                      </swing>
+                    </sound>
+                  </direction>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <type>eighth</type>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>D</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <type>eighth</type>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <type>eighth</type>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>F</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <type>eighth</type>
+                  </note>
+                </measure>
+                <measure number="2">
+                  <direction>
+                    <direction-type>
+                      <words>Straight</words>
+                    </direction-type>
+                    <sound>
+                      <swing>
+                        <straight />
+                      </swing>
+                    </sound>
+                  </direction>
+                  <note>
+                    <pitch>
+                      <step>G</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <type>eighth</type>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>A</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <type>eighth</type>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let arena = midly::Arena::new();
+        let midi = parse(src, &arena);
+
+        let mut position = 0;
+        let notes: Vec<_> = midi.tracks[0]
+            .iter()
+            .filter_map(|event| {
+                position += event.delta.as_int();
+                match event.kind {
+                    midly::TrackEventKind::Midi {
+                        message: midly::MidiMessage::NoteOn { key, .. },
+                        ..
+                    } => Some((position, key.as_int())),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        insta::assert_debug_snapshot!(notes);
+    }
+
+    #[test]
+    fn sound() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Violin</part-name>
+                  <score-instrument id="P1-I1">
+                    <instrument-name>Violin</instrument-name>
+                  </score-instrument>
+                  <score-instrument id="P1-I2">
+                    <instrument-name>Viola</instrument-name>
+                  </score-instrument>
+                  <midi-instrument id="P1-I1">
+                    <midi-channel>1</midi-channel>
+                    <midi-program>41</midi-program>
+                    <volume>80</volume>
+                    <pan>-45</pan>
+                  </midi-instrument>
+                  <midi-instrument id="P1-I2">
+                    <midi-channel>1</midi-channel>
+                    <midi-program>42</midi-program>
+                  </midi-instrument>
+                </score-part>
+                <score-part id="P2">
+                  <part-name>Trumpet</part-name>
+                  <midi-instrument id="P2-I1">
+                    <midi-channel>2</midi-channel>
+                    <midi-program>57</midi-program>
+                  </midi-instrument>
+                </score-part>
+                <score-part id="P3">
+                  <part-name>Cello</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <direction>
+                    <direction-type>
+                      <words>pizz.</words>
+                    </direction-type>
+                    <sound pizzicato="yes" dynamics="54" />
+                  </direction>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                  <direction>
+                    <direction-type>
+                      <words>arco</words>
+                    </direction-type>
+                    <sound pizzicato="no" />
+                  </direction>
+                  <note>
+                    <pitch>
+                      <step>D</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                  <sound damper-pedal="yes">
+                    <instrument-change id="P1-I2" />
+                  </sound>
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                  <sound>
+                    <midi-instrument id="P1-I1">
+                      <volume>100</volume>
+                    </midi-instrument>
+                    <offset>1</offset>
+                  </sound>
+                  <note>
+                    <pitch>
+                      <step>F</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                </measure>
+              </part>
+              <part id="P2">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <direction>
+                    <direction-type>
+                      <words>con sord.</words>
+                    </direction-type>
+                    <sound>
+                      <play>
+                        <mute>straight</mute>
+                      </play>
+                    </sound>
+                  </direction>
+                  <note>
+                    <pitch>
+                      <step>G</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                  <direction>
+                    <direction-type>
+                      <words>senza sord.</words>
+                    </direction-type>
+                    <sound>
+                      <play>
+                        <mute>off</mute>
+                      </play>
+                    </sound>
+                  </direction>
+                  <note>
+                    <pitch>
+                      <step>A</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                </measure>
+              </part>
+              <part id="P3">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <direction>
+                    <direction-type>
+                      <words>pizz.</words>
+                    </direction-type>
+                    <sound pizzicato="yes" />
+                  </direction>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>3</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                  <direction>
+                    <direction-type>
+                      <words>arco</words>
+                    </direction-type>
+                    <sound pizzicato="no" />
+                  </direction>
+                  <note>
+                    <pitch>
+                      <step>G</step>
+                      <octave>2</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let arena = midly::Arena::new();
+        let midi = parse(src, &arena);
+        insta::assert_debug_snapshot!(midi);
+    }
+
+    #[test]
+    fn multiple_rest() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                    <time>
+                      <beats>3</beats>
+                      <beat-type>4</beat-type>
+                    </time>
+                    <measure-style>
+                      <multiple-rest>2</multiple-rest>
+                    </measure-style>
+                  </attributes>
+                  <note>
+                    <rest measure="yes" />
+                    <duration>4</duration>
+                  </note>
+                </measure>
+                <measure number="2">
+                  <note>
+                    <rest measure="yes" />
+                    <duration>4</duration>
+                  </note>
+                </measure>
+                <measure number="3">
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>3</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let rests = measures::multiple_rests(&score.part[0]);
+
+        let arena = midly::Arena::new();
+        let midi = parse(src, &arena);
+
+        let mut position = 0;
+        let notes: Vec<_> = midi.tracks[0]
+            .iter()
+            .filter_map(|event| {
+                position += event.delta.as_int();
+                match event.kind {
+                    midly::TrackEventKind::Midi {
+                        message: midly::MidiMessage::NoteOn { key, .. },
+                        ..
+                    } => Some((position, key.as_int())),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        insta::assert_debug_snapshot!((rests, notes));
+    }
+
+    #[test]
+    fn measure_map() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="0">
+                  <attributes>
+                    <divisions>1</divisions>
+                    <time>
+                      <beats>4</beats>
+                      <beat-type>4</beat-type>
+                    </time>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>G</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                </measure>
+                <measure number="1">
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>4</duration>
+                  </note>
+                  <backup>
+                    <duration>4</duration>
+                  </backup>
+                  <forward>
+                    <duration>1</duration>
+                  </forward>
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                </measure>
+                <measure number="2">
+                  <note>
+                    <pitch>
+                      <step>D</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                </measure>
+                <measure number="2a" implicit="yes">
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                </measure>
+                <measure number="3">
+                  <note>
+                    <pitch>
+                      <step>F</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>4</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let map = measures::MeasureMap::new(&score.part[0]);
+        let measures: Vec<_> = map
+            .measures()
+            .iter()
+            .map(|measure| {
+                (
+                    measure.id.as_str(),
+                    measure.number.as_deref(),
+                    measure.start,
+                    measure.length,
+                    measure.pickup,
+                )
+            })
+            .collect();
+
+        let arena = midly::Arena::new();
+        let midi = parse(src, &arena);
+
+        let mut position = 0;
+        let notes: Vec<_> = midi.tracks[0]
+            .iter()
+            .filter_map(|event| {
+                position += event.delta.as_int();
+                match event.kind {
+                    midly::TrackEventKind::Midi {
+                        message: midly::MidiMessage::NoteOn { key, .. },
+                        ..
+                    } => Some((position, key.as_int())),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        insta::assert_debug_snapshot!((measures, notes));
+    }
+
+    #[test]
+    fn measure_numbers() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Flute</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="12">
+                  <attributes>
+                    <divisions>1</divisions>
+                    <time>
+                      <beats>2</beats>
+                      <beat-type>4</beat-type>
+                    </time>
+                  </attributes>
+                  <note>
+                    <rest measure="yes" />
+                    <duration>2</duration>
+                  </note>
+                </measure>
+                <measure number="12a">
+                  <note>
+                    <rest measure="yes" />
+                    <duration>2</duration>
+                  </note>
+                </measure>
+                <measure number="">
+                  <note>
+                    <rest measure="yes" />
+                    <duration>2</duration>
+                  </note>
+                </measure>
+                <measure number="14" text="B">
+                  <note>
+                    <rest measure="yes" />
+                    <duration>2</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let map = measures::MeasureMap::new(&score.part[0]);
+
+        let numbers: Vec<_> = map
+            .measures()
+            .iter()
+            .map(|measure| measure.number.as_deref())
+            .collect();
+        insta::assert_debug_snapshot!(numbers);
+    }
+
+    #[test]
+    fn measure_grid() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="0" implicit="yes">
+                  <attributes>
+                    <divisions>2</divisions>
+                    <time>
+                      <beats>6</beats>
+                      <beat-type>8</beat-type>
+                    </time>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>G</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                </measure>
+                <measure number="1">
+                  <barline location="left">
+                    <bar-style>heavy-light</bar-style>
+                    <repeat direction="forward" />
+                  </barline>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>6</duration>
+                  </note>
+                </measure>
+                <measure number="2">
+                  <barline location="left">
+                    <ending number="1" type="start">1.</ending>
+                  </barline>
+                  <note>
+                    <pitch>
+                      <step>D</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>6</duration>
+                  </note>
+                  <barline location="right">
+                    <bar-style>light-heavy</bar-style>
+                    <ending number="1" type="stop" />
+                    <repeat direction="backward" />
+                  </barline>
+                </measure>
+                <measure number="3">
+                  <barline location="left">
+                    <ending number="2" type="start">2.</ending>
+                  </barline>
+                  <sound tempo="60" />
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>6</duration>
+                  </note>
+                  <barline location="right">
+                    <ending number="2" type="discontinue" />
+                  </barline>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let grid: Vec<_> = grid::measure_grid(&score, &Options::default())
+            .into_iter()
+            .map(|measure| {
+                let beats: Vec<_> = measure
+                    .beats
+                    .iter()
+                    .map(|beat| (beat.tick, beat.seconds, beat.primary))
+                    .collect();
+                (
+                    measure.index,
+                    measure.number,
+                    measure.start,
+                    measure.end,
+                    measure.start_seconds,
+                    beats,
+                )
+            })
+            .collect();
+
+        insta::assert_debug_snapshot!(grid);
+    }
+
+    #[test]
+    fn jumps() {
+        let da_capo = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <barline location="left">
+                    <repeat direction="forward" />
+                  </barline>
+                </measure>
+                <measure number="2">
+                  <barline location="left">
+                    <ending number="1" type="start">1.</ending>
+                  </barline>
+                  <barline location="right">
+                    <ending number="1" type="stop" />
+                    <repeat direction="backward" />
+                  </barline>
+                </measure>
+                <measure number="3">
+                  <barline location="left">
+                    <ending number="2" type="start">2.</ending>
+                  </barline>
+                  <direction>
+                    <direction-type>
+                      <words>Fine</words>
+                    </direction-type>
+                    <sound fine="yes" />
+                  </direction>
+                  <barline location="right">
+                    <ending number="2" type="discontinue" />
+                  </barline>
+                </measure>
+                <measure number="4">
+                  <direction>
+                    <direction-type>
+                      <words>D.C. al Fine</words>
+                    </direction-type>
+                    <sound dacapo="yes" />
+                  </direction>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let dal_segno = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <note>
+                    <rest measure="yes" />
+                    <duration>4</duration>
+                  </note>
+                </measure>
+                <measure number="2">
+                  <direction>
+                    <direction-type>
+                      <segno />
+                    </direction-type>
+                    <sound segno="1" />
+                  </direction>
+                </measure>
+                <measure number="3">
+                  <direction>
+                    <direction-type>
+                      <words>To Coda</words>
+                    </direction-type>
+                    <sound tocoda="1" />
+                  </direction>
+                </measure>
+                <measure number="4">
+                  <direction>
+                    <direction-type>
+                      <words>D.S. al Coda</words>
+                    </direction-type>
+                    <sound dalsegno="1" />
+                  </direction>
+                </measure>
+                <measure number="5">
+                  <direction>
+                    <direction-type>
+                      <coda />
+                    </direction-type>
+                    <sound coda="1" />
+                  </direction>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let order = |src: &str| {
+            let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+            repeats::playback_order(&score.part[0])
+        };
+
+        assert_eq!(order(da_capo), [0, 1, 0, 2, 3, 0, 2]);
+        assert_eq!(order(dal_segno), [0, 1, 2, 3, 1, 2, 4]);
+    }
+
+    #[test]
+    fn tempo_map() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Flute</part-name>
+                </score-part>
+                <score-part id="P2">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <sound tempo="90" />
+                  <note>
+                    <rest measure="yes" />
+                    <duration>4</duration>
+                  </note>
+                </measure>
+              </part>
+              <part id="P2">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <note>
+                    <rest />
+                    <duration>2</duration>
+                  </note>
+                  <sound tempo="60" />
+                  <note>
+                    <rest />
+                    <duration>2</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let map = tempo::TempoMap::from_score(&score, &Options::default());
+
+        let changes: Vec<_> = map.changes().collect();
+        let seconds: Vec<_> = [0, 480, 960, 1440, 1920]
+            .into_iter()
+            .map(|tick| (tick, map.seconds(tick), map.tick(map.seconds(tick))))
+            .collect();
+
+        insta::assert_debug_snapshot!((changes, map.tempo(1000), seconds));
+    }
+
+    #[test]
+    fn tempo_offset() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Flute</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <direction>
+                    <direction-type>
+                      <words>Allegro</words>
+                    </direction-type>
+                    <offset>1</offset>
+                    <sound tempo="132" />
+                  </direction>
+                  <direction>
+                    <direction-type>
+                      <metronome>
+                        <beat-unit>quarter</beat-unit>
+                        <per-minute>60</per-minute>
+                      </metronome>
+                    </direction-type>
+                    <offset>2</offset>
+                  </direction>
+                  <note>
+                    <rest measure="yes" />
+                    <duration>4</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let map = tempo::TempoMap::from_score(&score, &Options::default());
+
+        // The sound ignores the offset of its direction, the metronome mark follows it
+        let changes: Vec<_> = map.changes().collect();
+        insta::assert_debug_snapshot!(changes);
+    }
+
+    #[test]
+    fn timeline() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>5</octave>
+                    </pitch>
+                    <duration>2</duration>
+                    <voice>1</voice>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>D</step>
+                      <octave>5</octave>
+                    </pitch>
+                    <duration>2</duration>
+                    <tie type="start" />
+                    <voice>1</voice>
+                  </note>
+                  <backup>
+                    <duration>4</duration>
+                  </backup>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>3</octave>
+                    </pitch>
+                    <duration>4</duration>
+                    <voice>2</voice>
+                  </note>
+                </measure>
+                <measure number="2">
+                  <note>
+                    <pitch>
+                      <step>D</step>
+                      <octave>5</octave>
+                    </pitch>
+                    <duration>4</duration>
+                    <tie type="stop" />
+                    <voice>1</voice>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let timeline = timeline::Timeline::new(&score, &Options::default());
+        let notes: Vec<_> = timeline.notes().collect();
+
+        insta::assert_debug_snapshot!(notes);
+    }
+
+    #[test]
+    fn note_index() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>4</duration>
+                  </note>
+                  <backup>
+                    <duration>4</duration>
+                  </backup>
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>G</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>3</duration>
+                  </note>
+                  <barline location="right">
+                    <repeat direction="backward" />
+                  </barline>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let timeline = timeline::Timeline::new(&score, &Options::default());
+        let index = timeline.index();
+
+        let id = timeline::NoteId {
+            part: 0,
+            measure: 0,
+            item: 3,
+        };
+        let key = |note: &&timeline::TimelineNote| (note.start, note.key);
+
+        let by_id: Vec<_> = index.by_id(id).map(|note| (note.start, note.key)).collect();
+        let starting: Vec<_> = index.starting(1000..2400).iter().map(key).collect();
+        let sounding: Vec<_> = index.sounding(2400).iter().map(key).collect();
+        let pitch = id.note(&score).and_then(|note| note.pitch.as_ref());
+
+        insta::assert_debug_snapshot!((by_id, starting, sounding, pitch));
+    }
+
+    #[test]
+    fn split_staves() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+                <score-part id="P2">
+                  <part-name>Violin</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                    <staves>2</staves>
+                  </attributes>
+                  <sound tempo="100" />
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>5</octave>
+                    </pitch>
+                    <duration>4</duration>
+                    <voice>1</voice>
+                    <staff>1</staff>
+                  </note>
+                  <backup>
+                    <duration>4</duration>
+                  </backup>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>3</octave>
+                    </pitch>
+                    <duration>4</duration>
+                    <voice>5</voice>
+                    <staff>2</staff>
+                  </note>
+                </measure>
+              </part>
+              <part id="P2">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>G</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>4</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let options = Options {
+            split_staves: true,
+            ..Default::default()
+        };
+
+        let arena = midly::Arena::new();
+        let midi = parse_with_options(src, &options, &arena);
+        let tracks: Vec<_> = midi
+            .tracks
+            .iter()
+            .map(|track| {
+                let mut name = None;
+                let mut events = Vec::new();
+
+                for event in track.iter() {
+                    match event.kind {
+                        midly::TrackEventKind::Meta(midly::MetaMessage::TrackName(text)) => {
+                            name = Some(String::from_utf8_lossy(text).into_owned());
+                        }
+                        midly::TrackEventKind::Meta(midly::MetaMessage::Tempo(tempo)) => {
+                            events.push(format!("tempo {}", tempo.as_int()));
+                        }
+                        midly::TrackEventKind::Midi {
+                            channel,
+                            message: midly::MidiMessage::NoteOn { key, .. },
+                        } => events.push(format!("channel {channel} key {key}")),
+                        _ => {}
+                    }
+                }
+
+                (name, events)
+            })
+            .collect();
+
+        insta::assert_debug_snapshot!((midi.header.format, tracks));
+    }
+
+    #[test]
+    fn voices() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Flute</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                    <time>
+                      <beats>4</beats>
+                      <beat-type>4</beat-type>
+                    </time>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>A</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>4</duration>
+                    <voice>1</voice>
+                  </note>
+                  <backup>
+                    <duration>4</duration>
+                  </backup>
+                  <forward>
+                    <duration>1</duration>
+                    <voice>2</voice>
+                  </forward>
+                  <note>
+                    <pitch>
+                      <step>F</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>2</duration>
+                    <voice>2</voice>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let mismatches = measures::validate_voices(&score.part[0]);
+
+        let timeline = timeline::Timeline::new(&score, &Options::default());
+        let second: Vec<_> = timeline
+            .voice(0, "2")
+            .map(|note| (note.start, note.key))
+            .collect();
+
+        let summary = |voices| {
+            let arena = midly::Arena::new();
+            let midi = parse_with_options(
+                src,
+                &Options {
+                    voices,
+                    ..Default::default()
+                },
+                &arena,
+            );
+
+            midi.tracks
+                .iter()
+                .map(|track| {
+                    track
+                        .iter()
+                        .filter_map(|event| match event.kind {
+                            midly::TrackEventKind::Meta(midly::MetaMessage::TrackName(text)) => {
+                                Some(String::from_utf8_lossy(text).into_owned())
+                            }
+                            midly::TrackEventKind::Midi {
+                                channel,
+                                message: midly::MidiMessage::NoteOn { key, .. },
+                            } => Some(format!("channel {channel} key {key}")),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        insta::assert_debug_snapshot!((
+            mismatches,
+            timeline.parts[0].voices.clone(),
+            second,
+            summary(VoiceOutput::Tracks),
+            summary(VoiceOutput::Channels),
+        ));
+    }
+
+    #[test]
+    fn fingering() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>2</duration>
+                    <notations>
+                      <technical>
+                        <fingering>1</fingering>
+                        <fingering substitution="yes">2</fingering>
+                      </technical>
+                    </notations>
+                  </note>
+                  <note>
+                    <chord />
+                    <pitch>
+                      <step>G</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>2</duration>
+                    <notations>
+                      <technical>
+                        <fingering placement="above">5</fingering>
+                        <fingering alternate="yes">4</fingering>
+                      </technical>
+                    </notations>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>D</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let timeline = timeline::Timeline::new(&score, &Options::default());
+        let fingering: Vec<_> = timeline
+            .notes()
+            .map(|note| (note.key, note.fingering.clone()))
+            .collect();
+
+        let options = Options {
+            fingering: true,
+            ..Default::default()
+        };
+        let arena = midly::Arena::new();
+        let midi = parse_with_options(src, &options, &arena);
+        let events: Vec<_> = midi.tracks[0]
+            .iter()
+            .filter_map(|event| match event.kind {
+                midly::TrackEventKind::Meta(midly::MetaMessage::Text(text)) => {
+                    Some(format!("text {}", String::from_utf8_lossy(text)))
+                }
+                midly::TrackEventKind::Midi {
+                    message: midly::MidiMessage::NoteOn { key, .. },
+                    ..
+                } => Some(format!("key {key}")),
+                _ => None,
+            })
+            .collect();
+
+        insta::assert_debug_snapshot!((fingering, events));
+    }
+
+    #[test]
+    fn lyrics() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Voice</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <lyric number="1">
+                      <syllabic>begin</syllabic>
+                      <text>Hap</text>
+                    </lyric>
+                    <lyric number="2">
+                      <syllabic>single</syllabic>
+                      <text>Good</text>
+                    </lyric>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>D</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <lyric number="1">
+                      <syllabic>end</syllabic>
+                      <text>py</text>
+                    </lyric>
+                    <lyric number="2">
+                      <syllabic>single</syllabic>
+                      <text>night</text>
+                      <extend />
+                    </lyric>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                    <lyric number="1">
+                      <syllabic>single</syllabic>
+                      <text>to</text>
+                      <elision />
+                      <syllabic>single</syllabic>
+                      <text>all</text>
+                    </lyric>
+                  </note>
+                  <note>
+                    <pitch>
+                      <step>F</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let timeline = timeline::Timeline::new(&score, &Options::default());
+        let lyrics: Vec<_> = timeline
+            .notes()
+            .map(|note| (note.key, note.lyrics.clone()))
+            .collect();
+
+        let verse = |verse: &str| {
+            let options = Options {
+                verse: Some(verse.to_string()),
+                ..Default::default()
+            };
+            let arena = midly::Arena::new();
+            let midi = parse_with_options(src, &options, &arena);
+
+            midi.tracks[0]
+                .iter()
+                .filter_map(|event| match event.kind {
+                    midly::TrackEventKind::Meta(midly::MetaMessage::Lyric(text)) => {
+                        Some(String::from_utf8_lossy(text).into_owned())
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        insta::assert_debug_snapshot!((lyrics, verse("1"), verse("2")));
+    }
+
+    #[test]
+    fn markers() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Voice</part-name>
+                </score-part>
+                <score-part id="P2">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>2</divisions>
+                  </attributes>
+                  <direction>
+                    <direction-type>
+                      <rehearsal>A</rehearsal>
+                    </direction-type>
+                  </direction>
+                  <direction>
+                    <direction-type>
+                      <segno />
+                    </direction-type>
+                    <direction-type>
+                      <words>Chorus</words>
+                    </direction-type>
+                    <direction-type>
+                      <dynamics>
+                        <mf />
+                      </dynamics>
+                    </direction-type>
+                    <offset sound="yes">2</offset>
+                    <sound dynamics="50" />
+                  </direction>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>8</duration>
+                  </note>
+                  <direction>
+                    <direction-type>
+                      <coda />
+                    </direction-type>
+                  </direction>
+                </measure>
+              </part>
+              <part id="P2">
+                <measure number="1">
+                  <attributes>
+                    <divisions>2</divisions>
+                  </attributes>
+                  <direction>
+                    <direction-type>
+                      <rehearsal>A</rehearsal>
+                    </direction-type>
+                  </direction>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>8</duration>
+                  </note>
+                  <direction>
+                    <direction-type>
+                      <coda />
+                    </direction-type>
+                  </direction>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let musicxml::MeasureItem::Direction(direction) = &score.part[0].measure[0].content[2]
+        else {
+            panic!("expected a direction");
+        };
+        let dynamics = &direction.direction_type[2].dynamics[0].marks;
+
+        let arena = midly::Arena::new();
+        let midi = parse(src, &arena);
+        let mut position = 0;
+        let events: Vec<_> = midi.tracks[0]
+            .iter()
+            .filter_map(|event| {
+                position += event.delta.as_int();
+
+                match event.kind {
+                    midly::TrackEventKind::Meta(midly::MetaMessage::Marker(text)) => Some(format!(
+                        "{position} marker {}",
+                        String::from_utf8_lossy(text)
+                    )),
+                    midly::TrackEventKind::Meta(midly::MetaMessage::Text(text)) => {
+                        Some(format!("{position} text {}", String::from_utf8_lossy(text)))
+                    }
+                    midly::TrackEventKind::Midi {
+                        channel,
+                        message: midly::MidiMessage::NoteOn { vel, .. },
+                    } => Some(format!("{position} channel {channel} velocity {vel}")),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        insta::assert_debug_snapshot!((dynamics, events));
+    }
+
+    #[test]
+    fn harmony() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Lead</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                    <time>
+                      <beats>4</beats>
+                      <beat-type>4</beat-type>
+                    </time>
+                  </attributes>
+                  <harmony>
+                    <root>
+                      <root-step>C</root-step>
+                    </root>
+                    <kind>major-seventh</kind>
+                  </harmony>
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>5</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                  <harmony>
+                    <root>
+                      <root-step>G</root-step>
+                    </root>
+                    <kind>dominant</kind>
+                    <bass>
+                      <bass-step>B</bass-step>
+                    </bass>
+                  </harmony>
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>5</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                </measure>
+                <measure number="2">
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>5</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                  <harmony>
+                    <root>
+                      <root-step>F</root-step>
+                      <root-alter>1</root-alter>
+                    </root>
+                    <kind>half-diminished</kind>
+                    <inversion>1</inversion>
+                  </harmony>
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>5</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                </measure>
+                <measure number="3">
+                  <harmony>
+                    <root>
+                      <root-step>D</root-step>
+                    </root>
+                    <kind>minor</kind>
+                    <degree>
+                      <degree-value>9</degree-value>
+                      <degree-alter>0</degree-alter>
+                      <degree-type>add</degree-type>
+                    </degree>
+                    <degree>
+                      <degree-value>5</degree-value>
+                      <degree-alter>-1</degree-alter>
+                      <degree-type>alter</degree-type>
+                    </degree>
+                  </harmony>
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>5</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                  <harmony>
+                    <numeral>
+                      <numeral-root>2</numeral-root>
+                    </numeral>
+                    <kind>minor-seventh</kind>
+                  </harmony>
+                  <harmony>
+                    <kind text="N.C.">none</kind>
+                  </harmony>
+                  <note>
+                    <pitch>
+                      <step>E</step>
+                      <octave>5</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let names: Vec<_> = score.part[0]
+            .measure
+            .iter()
+            .flat_map(|measure| &measure.content)
+            .filter_map(|item| match item {
+                musicxml::MeasureItem::Harmony(harmony) => Some(harmony.name()),
+                _ => None,
+            })
+            .collect();
+
+        let options = Options {
+            comping: true,
+            ..Default::default()
+        };
+        let timeline = timeline::Timeline::new(&score, &options);
+        let mut chords: Vec<(usize, usize, Vec<u8>)> = Vec::new();
+        for note in timeline.notes().filter(|note| note.id.part == 1) {
+            match chords.last_mut() {
+                Some((start, duration, keys)) if *start == note.start => {
+                    assert_eq!(*duration, note.duration);
+                    keys.push(note.key);
+                }
+                _ => chords.push((note.start, note.duration, vec![note.key])),
+            }
+        }
+
+        let arena = midly::Arena::new();
+        let midi = parse_with_options(src, &options, &arena);
+        let channels: std::collections::BTreeSet<_> = midi.tracks[0]
+            .iter()
+            .filter_map(|event| match event.kind {
+                midly::TrackEventKind::Midi { channel, .. } => Some(channel.as_int()),
+                _ => None,
+            })
+            .collect();
+
+        insta::assert_debug_snapshot!((names, timeline.parts[1].name.clone(), chords, channels));
+    }
+
+    #[test]
+    fn continuo() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Continuo</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                    <key>
+                      <fifths>1</fifths>
+                    </key>
+                    <time>
+                      <beats>4</beats>
+                      <beat-type>4</beat-type>
+                    </time>
+                  </attributes>
+                  <figured-bass>
+                    <figure>
+                      <figure-number>5</figure-number>
+                    </figure>
+                  </figured-bass>
+                  <note>
+                    <pitch>
+                      <step>G</step>
+                      <octave>2</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                  <figured-bass>
+                    <figure>
+                      <figure-number>6</figure-number>
+                    </figure>
+                  </figured-bass>
+                  <note>
+                    <pitch>
+                      <step>B</step>
+                      <octave>2</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                  <figured-bass>
+                    <figure>
+                      <figure-number>6</figure-number>
+                    </figure>
+                    <figure>
+                      <figure-number>4</figure-number>
+                    </figure>
+                    <duration>1</duration>
+                  </figured-bass>
+                  <figured-bass>
+                    <figure>
+                      <prefix>sharp</prefix>
+                    </figure>
+                    <duration>1</duration>
+                  </figured-bass>
+                  <note>
+                    <pitch>
+                      <step>D</step>
+                      <octave>3</octave>
+                    </pitch>
+                    <duration>2</duration>
+                  </note>
+                </measure>
+                <measure number="2">
+                  <figured-bass>
+                    <figure>
+                      <prefix>flat</prefix>
+                      <figure-number>7</figure-number>
+                    </figure>
+                  </figured-bass>
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>3</octave>
+                    </pitch>
+                    <duration>4</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let options = Options {
+            continuo: true,
+            ..Default::default()
+        };
+        let timeline = timeline::Timeline::new(&score, &options);
+
+        let mut chords: Vec<(usize, usize, Vec<u8>)> = Vec::new();
+        for note in timeline.notes().filter(|note| note.id.part == 1) {
+            match chords.last_mut() {
+                Some((start, duration, keys)) if *start == note.start => {
+                    assert_eq!(*duration, note.duration);
+                    keys.push(note.key);
+                }
+                _ => chords.push((note.start, note.duration, vec![note.key])),
+            }
+        }
+
+        insta::assert_debug_snapshot!((timeline.parts[1].name.clone(), chords));
+    }
+
+    #[test]
+    fn metadata() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <work>
+                <work-number>Op. 27</work-number>
+                <work-title>Moonlight Sonata</work-title>
+              </work>
+              <movement-number>1</movement-number>
+              <movement-title>Adagio sostenuto</movement-title>
+              <identification>
+                <creator type="composer">Ludwig van Beethoven</creator>
+                <creator type="lyricist">Nobody</creator>
+                <creator type="poet">No One</creator>
+                <creator type="arranger">Someone</creator>
+                <rights>Public Domain</rights>
+                <encoding>
+                  <encoding-date>2024-01-01</encoding-date>
+                  <software>NeoClef</software>
+                </encoding>
+                <source>Urtext</source>
+                <relation type="isPartOf">Sonatas</relation>
+                <miscellaneous>
+                  <miscellaneous-field name="difficulty">easy</miscellaneous-field>
+                </miscellaneous>
+              </identification>
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <sound tempo="60" />
+                  <note>
+                    <pitch>
+                      <step>C</step>
+                      <octave>4</octave>
+                    </pitch>
+                    <duration>1</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let metadata = metadata::ScoreMetadata::new(&score);
+        let identification = score.identification.as_ref().unwrap();
+        let misc = &identification
+            .miscellaneous
+            .as_ref()
+            .unwrap()
+            .miscellaneous_field[0];
+
+        let arena = midly::Arena::new();
+        let midi = parse(src, &arena);
+        let header: Vec<_> = midi.tracks[0]
+            .iter()
+            .take(3)
+            .map(|event| match event.kind {
+                midly::TrackEventKind::Meta(midly::MetaMessage::TrackName(text)) => {
+                    format!("name {}", String::from_utf8_lossy(text))
+                }
+                midly::TrackEventKind::Meta(midly::MetaMessage::Copyright(text)) => {
+                    format!("copyright {}", String::from_utf8_lossy(text))
+                }
+                kind => format!("{kind:?}"),
+            })
+            .collect();
+
+        insta::assert_debug_snapshot!((
+            metadata,
+            score.movement_number,
+            identification.source.clone(),
+            (&misc.name, &misc.value),
+            header,
+        ));
+    }
+
+    #[test]
+    fn layout() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <defaults>
+                <scaling>
+                  <millimeters>7</millimeters>
+                  <tenths>40</tenths>
+                </scaling>
+                <page-layout>
+                  <page-height>1600</page-height>
+                  <page-width>1200</page-width>
+                  <page-margins type="even">
+                    <left-margin>80</left-margin>
+                    <right-margin>120</right-margin>
+                    <top-margin>80</top-margin>
+                    <bottom-margin>80</bottom-margin>
+                  </page-margins>
+                  <page-margins type="odd">
+                    <left-margin>120</left-margin>
+                    <right-margin>80</right-margin>
+                    <top-margin>80</top-margin>
+                    <bottom-margin>80</bottom-margin>
+                  </page-margins>
+                </page-layout>
+                <system-layout>
+                  <system-margins>
+                    <left-margin>0</left-margin>
+                    <right-margin>0</right-margin>
+                  </system-margins>
+                  <system-distance>120</system-distance>
+                  <top-system-distance>200</top-system-distance>
+                </system-layout>
+                <staff-layout>
+                  <staff-distance>60</staff-distance>
+                </staff-layout>
+                <appearance>
+                  <line-width type="stem">1</line-width>
+                  <note-size type="grace">70</note-size>
+                </appearance>
+                <music-font font-family="Leland" />
+                <word-font font-family="Edwin" font-size="10" />
+                <lyric-font font-family="Edwin" font-size="10" />
+              </defaults>
+              <credit page="1">
+                <credit-type>title</credit-type>
+                <credit-words default-x="600" default-y="1500" justify="center" valign="top" font-size="22">Untitled</credit-words>
+              </credit>
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Piano</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <print>
+                    <system-layout>
+                      <top-system-distance>170</top-system-distance>
+                    </system-layout>
+                  </print>
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <note>
+                    <rest />
+                    <duration>4</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let defaults = score.defaults.as_ref().unwrap();
+        let system = defaults.system_layout.as_ref().unwrap();
+        let staff = &defaults.staff_layout[0];
+
+        let credit = &score.credit[0];
+        let words = &credit.credit_words[0];
+        let title = (
+            &credit.credit_type,
+            &words.text,
+            defaults.millimeters(words.default_x.unwrap()),
+            defaults.millimeters(words.default_y.unwrap()),
+        );
+
+        let sample: musicxml::ScorePartwise =
+            quick_xml::de::from_str(include_str!("../schema/1.musicxml")).unwrap();
+        let sample = sample.defaults.and_then(|defaults| defaults.page(1));
+
+        insta::assert_debug_snapshot!((
+            defaults.page(1),
+            defaults.page(2),
+            defaults.millimeters(system.system_distance.unwrap()),
+            defaults.millimeters(staff.staff_distance.unwrap()),
+            title,
+            sample.map(|page| (page.width.round(), page.height.round())),
+        ));
+    }
+
+    #[test]
+    fn print_breaks() {
+        let src = xml!(
+            <score-partwise version="4.0">
+              <part-list>
+                <score-part id="P1">
+                  <part-name>Flute</part-name>
+                </score-part>
+              </part-list>
+              <part id="P1">
+                <measure number="1">
+                  <print>
+                    <system-layout>
+                      <top-system-distance>170</top-system-distance>
+                    </system-layout>
+                  </print>
+                  <attributes>
+                    <divisions>1</divisions>
+                  </attributes>
+                  <note>
+                    <rest />
+                    <duration>4</duration>
+                  </note>
+                </measure>
+                <measure number="2">
+                  <print new-system="yes" staff-spacing="70">
+                    <staff-layout number="2">
+                      <staff-distance>65</staff-distance>
+                    </staff-layout>
+                  </print>
+                  <note>
+                    <rest />
+                    <duration>4</duration>
+                  </note>
+                </measure>
+                <measure number="3">
+                  <print new-page="yes" page-number="2">
+                    <measure-numbering system="only-top">system</measure-numbering>
+                  </print>
+                  <note>
+                    <rest />
+                    <duration>4</duration>
+                  </note>
+                </measure>
+                <measure number="4">
+                  <print>
+                    <measure-layout>
+                      <measure-distance>20</measure-distance>
+                    </measure-layout>
+                  </print>
+                  <note>
+                    <rest />
+                    <duration>4</duration>
+                  </note>
+                </measure>
+                <measure number="5">
+                  <print blank-page="1">
+                    <part-name-display>
+                      <display-text>Flute</display-text>
+                    </part-name-display>
+                  </print>
+                  <note>
+                    <rest />
+                    <duration>4</duration>
+                  </note>
+                </measure>
+                <measure number="6">
+                  <print new-page="yes" blank-page="1" />
+                  <note>
+                    <rest />
+                    <duration>4</duration>
+                  </note>
+                </measure>
+              </part>
+            </score-partwise>
+        );
+
+        let score: musicxml::ScorePartwise = quick_xml::de::from_str(src).unwrap();
+        let breaks = layout::breaks(&score.part[0]);
+
+        let prints: Vec<_> = score.part[0]
+            .measure
+            .iter()
+            .flat_map(|measure| &measure.content)
+            .filter_map(|item| match item {
+                musicxml::MeasureItem::Print(print) => Some(print),
+                _ => None,
+            })
+            .collect();
+        let numbering = prints[2].measure_numbering.as_ref().map(|n| &n.value);
+        let name = prints[4]
+            .part_name_display
+            .as_ref()
+            .map(|name| &name.display_text[0].text);
+
+        insta::assert_debug_snapshot!((breaks, numbering, name));
+    }
+}
@@ -0,0 +1,334 @@
+//! Where measures start and how they are numbered, and multi-measure rests spanning several
+//! of them.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    TICKS_PER_QUARTER_NOTE_F64,
+    musicxml::{Measure, MeasureItem, Note, Part, Time},
+    timeline,
+};
+
+impl Time {
+    /// Length of a measure in quarter notes, `None` when the signature is not made of numbers.
+    ///
+    /// Additive beats like "3+2" are added up.
+    pub fn quarters(&self) -> Option<f64> {
+        let beats = self
+            .beats
+            .split('+')
+            .map(|beats| beats.trim().parse::<f64>().ok())
+            .sum::<Option<f64>>()?;
+        let beat_type: f64 = self.beat_type.trim().parse().ok()?;
+
+        (beat_type > 0.0).then(|| beats * 4.0 / beat_type)
+    }
+
+    /// Length of a measure in ticks.
+    pub fn ticks(&self) -> Option<usize> {
+        self.quarters()
+            .map(|quarters| (quarters * TICKS_PER_QUARTER_NOTE_F64).round() as usize)
+    }
+}
+
+impl Note {
+    /// A rest lasting the whole measure, whatever its time signature.
+    pub fn is_measure_rest(&self) -> bool {
+        self.rest
+            .as_ref()
+            .is_some_and(|rest| rest.measure.as_deref() == Some("yes"))
+    }
+}
+
+/// Length of a note in ticks. Whole-measure rests last `measure` ticks when the time signature
+/// is known, their `<duration>` otherwise.
+pub(crate) fn note_ticks(note: &Note, divisions: f64, measure: Option<usize>) -> usize {
+    match measure {
+        Some(measure) if note.is_measure_rest() => measure,
+        _ => duration_ticks(&note.duration, divisions),
+    }
+}
+
+/// Ticks of a `<backup>` or `<forward>` of `duration` divisions.
+pub(crate) fn duration_ticks(duration: &str, divisions: f64) -> usize {
+    let duration: f64 = duration.parse().unwrap();
+    ((duration / divisions) * TICKS_PER_QUARTER_NOTE_F64) as usize
+}
+
+/// A measure of a part, placed in time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeasureSpan {
+    /// Index of the measure in `Part::measure`.
+    pub index: usize,
+    /// The `number` attribute, which identifies the measure in the document.
+    pub id: String,
+    /// Number shown to musicians: the `text` attribute, or the `number` one, `None` for
+    /// measures left out of the numbering (pickups and other implicit measures).
+    pub number: Option<String>,
+    /// Start of the measure, in ticks.
+    pub start: usize,
+    /// Length of the measure in ticks, as long as its longest voice.
+    pub length: usize,
+    /// Time signature in effect.
+    pub time: Option<Time>,
+    /// The measure is shorter than its time signature and comes first: an anacrusis.
+    pub pickup: bool,
+}
+
+impl MeasureSpan {
+    pub fn end(&self) -> usize {
+        self.start + self.length
+    }
+}
+
+/// Start, length and displayed number of every measure of a part.
+#[derive(Debug, Clone, Default)]
+pub struct MeasureMap {
+    measures: Vec<MeasureSpan>,
+}
+
+impl MeasureMap {
+    pub fn new(part: &Part) -> Self {
+        let mut divisions = 1.0;
+        let mut time: Option<Time> = None;
+        let mut start = 0;
+        let mut numbered = 0;
+
+        let mut measures = Vec::with_capacity(part.measure.len());
+
+        for (index, measure) in part.measure.iter().enumerate() {
+            let length = measure_length(measure, &mut divisions, &mut time, &mut BTreeMap::new());
+            let nominal = time.as_ref().and_then(Time::ticks);
+
+            // Measures without notes last as long as their time signature says
+            let length = match (length, nominal) {
+                (0, Some(nominal)) => nominal,
+                _ => length,
+            };
+
+            let pickup = index == 0 && nominal.is_some_and(|nominal| length < nominal);
+            let implicit = pickup || measure.implicit.as_deref() == Some("yes");
+
+            let number = match (&measure.text, implicit) {
+                (Some(text), _) => Some(text.clone()),
+                (None, true) => None,
+                (None, false) => {
+                    numbered += 1;
+
+                    // Counting on from the last numbered measure when the number is missing
+                    let id = measure.number.trim();
+                    if let Ok(id) = id.parse() {
+                        numbered = id;
+                    }
+
+                    if id.is_empty() {
+                        Some(numbered.to_string())
+                    } else {
+                        Some(id.to_string())
+                    }
+                }
+            };
+
+            measures.push(MeasureSpan {
+                index,
+                id: measure.number.clone(),
+                number,
+                start,
+                length,
+                time: time.clone(),
+                pickup,
+            });
+
+            start += length;
+        }
+
+        Self { measures }
+    }
+
+    pub fn measures(&self) -> &[MeasureSpan] {
+        &self.measures
+    }
+
+    /// The measure at `index` in `Part::measure`.
+    pub fn get(&self, index: usize) -> Option<&MeasureSpan> {
+        self.measures.get(index)
+    }
+
+    /// The measure sounding at `tick`.
+    pub fn at(&self, tick: usize) -> Option<&MeasureSpan> {
+        let index = self
+            .measures
+            .partition_point(|measure| measure.start <= tick)
+            .checked_sub(1)?;
+
+        self.measures
+            .get(index)
+            .filter(|measure| tick < measure.end())
+    }
+
+    /// End of the last measure, in ticks.
+    pub fn end(&self) -> usize {
+        self.measures.last().map_or(0, MeasureSpan::end)
+    }
+}
+
+/// Length of the longest voice of a measure in ticks, keeping track of the divisions and time
+/// signature set along the way. The notes and forwards of each voice are added up in `voices`.
+fn measure_length(
+    measure: &Measure,
+    divisions: &mut f64,
+    time: &mut Option<Time>,
+    voices: &mut BTreeMap<String, usize>,
+) -> usize {
+    let mut position = 0usize;
+    let mut length = 0usize;
+
+    for item in measure.content.iter() {
+        match item {
+            MeasureItem::Attributes(attributes) => {
+                if let Some(d) = attributes.divisions.as_ref() {
+                    *divisions = d.trim().parse().unwrap();
+                }
+
+                if let Some(t) = attributes.time.first() {
+                    *time = Some(t.clone());
+                }
+            }
+            MeasureItem::Note(note) if note.chord.is_none() => {
+                let measure = time.as_ref().and_then(Time::ticks);
+                let ticks = note_ticks(note, *divisions, measure);
+                position += ticks;
+                *voices.entry(timeline::voice(note).to_string()).or_default() += ticks;
+            }
+            MeasureItem::Backup(backup) => {
+                position = position.saturating_sub(duration_ticks(&backup.duration, *divisions));
+            }
+            MeasureItem::Forward(forward) => {
+                let ticks = duration_ticks(&forward.duration, *divisions);
+                position += ticks;
+
+                if let Some(voice) = forward.voice.as_deref() {
+                    *voices.entry(voice.trim().to_string()).or_default() += ticks;
+                }
+            }
+            _ => {}
+        }
+
+        length = length.max(position);
+    }
+
+    length
+}
+
+/// A voice whose notes do not add up to the length of its measure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoiceMismatch {
+    /// Index of the measure in `Part::measure`.
+    pub measure: usize,
+    pub voice: String,
+    /// Length of the voice's notes and forwards, in ticks.
+    pub length: usize,
+    /// Length the measure should have, in ticks.
+    pub expected: usize,
+}
+
+/// Voices of a part that are shorter or longer than their measure.
+///
+/// Measures are expected to follow their time signature, except pickups and other implicit
+/// measures which may be as short as their longest voice. Notes without a `<voice>` belong to
+/// voice "1".
+pub fn validate_voices(part: &Part) -> Vec<VoiceMismatch> {
+    let map = MeasureMap::new(part);
+    let mut divisions = 1.0;
+    let mut time: Option<Time> = None;
+    let mut mismatches = Vec::new();
+
+    for (index, measure) in part.measure.iter().enumerate() {
+        let mut voices = BTreeMap::new();
+        measure_length(measure, &mut divisions, &mut time, &mut voices);
+
+        let Some(span) = map.get(index) else {
+            continue;
+        };
+
+        let implicit = span.pickup || measure.implicit.as_deref() == Some("yes");
+        let expected = match time.as_ref().and_then(Time::ticks) {
+            Some(nominal) if !implicit => nominal,
+            _ => span.length,
+        };
+
+        for (voice, length) in voices {
+            if length != expected {
+                mismatches.push(VoiceMismatch {
+                    measure: index,
+                    voice,
+                    length,
+                    expected,
+                });
+            }
+        }
+    }
+
+    mismatches
+}
+
+/// Empty measures displayed as a single multi-measure rest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultipleRestSpan {
+    /// Index of the first measure in `Part::measure`.
+    pub measure: usize,
+    /// Number of measures.
+    pub count: usize,
+    /// Start of the first measure, in ticks.
+    pub start: usize,
+    /// End of the last measure, in ticks.
+    pub end: usize,
+}
+
+/// Multi-measure rests of a part, from `<measure-style><multiple-rest>`.
+///
+/// The span covers the full length of every measure of the rest. Measures missing from the
+/// part are assumed to follow the last time signature.
+pub fn multiple_rests(part: &Part) -> Vec<MultipleRestSpan> {
+    let map = MeasureMap::new(part);
+    let mut spans = Vec::new();
+
+    for (index, measure) in part.measure.iter().enumerate() {
+        let count = measure
+            .content
+            .iter()
+            .filter_map(|item| match item {
+                MeasureItem::Attributes(attributes) => Some(attributes),
+                _ => None,
+            })
+            .flat_map(|attributes| &attributes.measure_style)
+            .find_map(|style| style.multiple_rest.as_ref())
+            .map(|rest| rest.count as usize);
+
+        let (Some(count), Some(first)) = (count.filter(|count| *count > 0), map.get(index)) else {
+            continue;
+        };
+
+        let end = (index..index + count).fold(first.start, |end, measure| {
+            let length = match map.get(measure) {
+                Some(measure) => measure.length,
+                None => map
+                    .measures()
+                    .last()
+                    .and_then(|last| last.time.as_ref())
+                    .and_then(Time::ticks)
+                    .unwrap_or(0),
+            };
+            end + length
+        });
+
+        spans.push(MultipleRestSpan {
+            measure: index,
+            count,
+            start: first.start,
+            end,
+        });
+    }
+
+    spans
+}
@@ -0,0 +1,73 @@
+//! Who wrote a score and what it is called, as shown in a song list.
+
+use crate::musicxml::ScorePartwise;
+
+/// Names of a score, gathered from `<work>`, `<movement-title>` and `<identification>`.
+///
+/// Line breaks and runs of spaces are collapsed into single spaces, several creators of the
+/// same kind are joined with ", ".
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScoreMetadata {
+    /// The work title, or the movement title for scores that are not part of a larger work.
+    pub title: Option<String>,
+    /// The movement title, when the score has a work title as well.
+    pub movement: Option<String>,
+    pub composer: Option<String>,
+    /// Lyricists and poets.
+    pub lyricist: Option<String>,
+    pub arranger: Option<String>,
+    pub copyright: Option<String>,
+}
+
+impl ScoreMetadata {
+    pub fn new(score: &ScorePartwise) -> Self {
+        let text =
+            |text: Option<&String>| text.map(|text| collapse(text)).filter(|t| !t.is_empty());
+
+        let work = text(
+            score
+                .work
+                .as_ref()
+                .and_then(|work| work.work_title.as_ref()),
+        );
+        let movement = text(score.movement_title.as_ref());
+
+        let (title, movement) = match (work, movement) {
+            (Some(work), movement) => (Some(work), movement),
+            (None, movement) => (movement, None),
+        };
+
+        let identification = score.identification.as_ref();
+        let creators = |kinds: &[&str]| {
+            let names: Vec<String> = identification
+                .into_iter()
+                .flat_map(|identification| &identification.creator)
+                .filter(|creator| kinds.contains(&creator.kind.as_deref().unwrap_or("")))
+                .map(|creator| collapse(&creator.value))
+                .filter(|name| !name.is_empty())
+                .collect();
+
+            (!names.is_empty()).then(|| names.join(", "))
+        };
+
+        let rights: Vec<String> = identification
+            .into_iter()
+            .flat_map(|identification| &identification.rights)
+            .map(|rights| collapse(&rights.value))
+            .filter(|rights| !rights.is_empty())
+            .collect();
+
+        Self {
+            title,
+            movement,
+            composer: creators(&["composer"]),
+            lyricist: creators(&["lyricist", "poet"]),
+            arranger: creators(&["arranger"]),
+            copyright: (!rights.is_empty()).then(|| rights.join(", ")),
+        }
+    }
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
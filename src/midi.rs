@@ -0,0 +1,355 @@
+//! Serialization of a [`Timeline`] into a Standard MIDI File.
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{
+    Options, TICKS_PER_QUARTER_NOTE, VoiceOutput,
+    channels::Channels,
+    metadata::ScoreMetadata,
+    musicxml::StaffNumber,
+    timeline::{AnnotationKind, Control, Finger, Timeline, TimelineEvent, TimelineNote},
+};
+
+pub(crate) type Events<'a> = BTreeMap<usize, Vec<midly::TrackEvent<'a>>>;
+
+/// Name of the track of `staff` of a part split into one track per staff.
+fn staff_name(name: &str, staff: StaffNumber, staves: StaffNumber) -> String {
+    match (staves, staff) {
+        (2, 1) => format!("{name} — RH"),
+        (2, 2) => format!("{name} — LH"),
+        _ => format!("{name} — Staff {staff}"),
+    }
+}
+
+/// Fingering written the way scores show it: "3", "3-1" for a substitution and "3 (2)" for an
+/// alternate.
+fn fingering_text(fingering: &[Finger]) -> Option<String> {
+    let mut text = String::new();
+
+    for finger in fingering {
+        if finger.alternate {
+            text.push_str(&format!(" ({})", finger.finger));
+        } else if finger.substitution {
+            text.push_str(&format!("-{}", finger.finger));
+        } else {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(&finger.finger);
+        }
+    }
+
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// How the notes of a part are spread over tracks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Split {
+    None,
+    Staves,
+    Voices,
+}
+
+/// Tracks of the file and where the events of each part go.
+struct Tracks<'a> {
+    tracks: Vec<Events<'a>>,
+    /// First track of each part, followed by one track per staff or voice when split.
+    first: Vec<usize>,
+    split: Vec<Split>,
+}
+
+impl<'a> Tracks<'a> {
+    fn new(timeline: &Timeline, options: &Options, arena: &'a midly::Arena) -> Self {
+        let voices = options.voices == VoiceOutput::Tracks;
+
+        if !options.split_staves && !voices {
+            return Self {
+                tracks: vec![BTreeMap::new()],
+                first: vec![0; timeline.parts.len()],
+                split: vec![Split::None; timeline.parts.len()],
+            };
+        }
+
+        // Track 0 holds the tempo
+        let mut tracks = vec![BTreeMap::new()];
+        let mut first = Vec::with_capacity(timeline.parts.len());
+        let mut split = Vec::with_capacity(timeline.parts.len());
+
+        for part in timeline.parts.iter() {
+            first.push(tracks.len());
+
+            let name = part.name.as_deref().unwrap_or(&part.id);
+            let names: Vec<String> = if voices && part.voices.len() > 1 {
+                split.push(Split::Voices);
+                part.voices
+                    .iter()
+                    .map(|voice| format!("{name} — Voice {voice}"))
+                    .collect()
+            } else if options.split_staves && part.staves > 1 {
+                split.push(Split::Staves);
+                (1..=part.staves)
+                    .map(|staff| staff_name(name, staff, part.staves))
+                    .collect()
+            } else {
+                split.push(Split::None);
+                vec![name.to_string()]
+            };
+
+            for name in names {
+                let name = arena.add_vec(name.into_bytes());
+
+                let mut track = BTreeMap::new();
+                push(
+                    &mut track,
+                    0,
+                    midly::TrackEventKind::Meta(midly::MetaMessage::TrackName(name)),
+                );
+                tracks.push(track);
+            }
+        }
+
+        Self {
+            tracks,
+            first,
+            split,
+        }
+    }
+
+    fn part(&mut self, part: usize) -> &mut Events<'a> {
+        &mut self.tracks[self.first[part]]
+    }
+
+    /// Index of the track of a note of the `voice`th voice of its part.
+    fn note(&self, note: &TimelineNote, voice: usize) -> usize {
+        let part = note.id.part;
+        let offset = match self.split[part] {
+            Split::None => 0,
+            Split::Staves => note.staff.saturating_sub(1) as usize,
+            Split::Voices => voice,
+        };
+
+        self.first[part] + offset
+    }
+}
+
+/// A MIDI file playing the timeline.
+///
+/// Every part gets a channel of its own, notes detuned by microtones or swept by a glissando are
+/// moved to channels bent accordingly. Everything goes to a single track, unless `split_staves` or `voices` ask
+/// for a track per staff or voice.
+///
+/// Rehearsal marks, segni and codas become markers of the first track, words text events of
+/// their part. The first track is named after the score and carries its copyright notice.
+/// Names, lyrics and other texts of the file are kept in `arena`.
+pub fn write<'a>(
+    timeline: &Timeline,
+    metadata: &ScoreMetadata,
+    options: &Options,
+    arena: &'a midly::Arena,
+) -> midly::Smf<'a> {
+    let text = |text: String| -> &'a [u8] { arena.add_vec(text.into_bytes()) };
+
+    let voice_channels = options.voices == VoiceOutput::Channels;
+    let mut channels = Channels::new(&timeline.parts, voice_channels);
+    let mut tracks = Tracks::new(timeline, options, arena);
+    let mut markers = HashSet::new();
+
+    for event in timeline.events.iter() {
+        match event {
+            TimelineEvent::Note(note) => {
+                let part = note.id.part;
+                let voice = timeline.parts[part]
+                    .voices
+                    .iter()
+                    .position(|voice| *voice == note.voice)
+                    .unwrap_or(0);
+
+                let track = tracks.note(note, voice);
+                let main = channels.voice(part, voice);
+                let channel = channels.assign(track, main, note);
+                let events = &mut tracks.tracks[track];
+
+                let verse = options.verse.as_deref();
+                let syllable = note
+                    .lyrics
+                    .iter()
+                    .find(|lyric| Some(&*lyric.verse) == verse);
+                if let Some(syllable) = syllable {
+                    push(
+                        events,
+                        note.start,
+                        midly::TrackEventKind::Meta(midly::MetaMessage::Lyric(text(
+                            syllable.karaoke(),
+                        ))),
+                    );
+                }
+
+                if let Some(fingering) =
+                    fingering_text(&note.fingering).filter(|_| options.fingering)
+                {
+                    push(
+                        events,
+                        note.start,
+                        midly::TrackEventKind::Meta(midly::MetaMessage::Text(text(fingering))),
+                    );
+                }
+
+                push(
+                    events,
+                    note.start,
+                    note_on(channel, note.key, note.velocity),
+                );
+                push(events, note.end(), note_off(channel, note.key));
+            }
+            TimelineEvent::Annotation(annotation) => match annotation.kind {
+                // Every part repeats the rehearsal marks, the file needs them once
+                AnnotationKind::Marker => {
+                    if markers.insert((annotation.position, &annotation.text)) {
+                        push(
+                            &mut tracks.tracks[0],
+                            annotation.position,
+                            midly::TrackEventKind::Meta(midly::MetaMessage::Marker(text(
+                                annotation.text.clone(),
+                            ))),
+                        );
+                    }
+                }
+                AnnotationKind::Words => push(
+                    tracks.part(annotation.part),
+                    annotation.position,
+                    midly::TrackEventKind::Meta(midly::MetaMessage::Text(text(
+                        annotation.text.clone(),
+                    ))),
+                ),
+            },
+            TimelineEvent::Control(change) => {
+                let events = tracks.part(change.part);
+                let message = control_message(change.control);
+
+                // Voices on channels of their own play the same instrument
+                for channel in channels.part(change.part) {
+                    push(
+                        events,
+                        change.position,
+                        midly::TrackEventKind::Midi {
+                            channel: (*channel).into(),
+                            message,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    channels.finish(&mut tracks.tracks, &timeline.events);
+
+    for (position, microseconds_per_quarter_note) in timeline.tempo.changes() {
+        // Ahead of the notes starting at the same time
+        tracks.tracks[0].entry(position).or_default().insert(
+            0,
+            midly::TrackEvent {
+                delta: 0.into(),
+                kind: midly::TrackEventKind::Meta(midly::MetaMessage::Tempo(
+                    microseconds_per_quarter_note.into(),
+                )),
+            },
+        );
+    }
+
+    let title = match (&metadata.title, &metadata.movement) {
+        (Some(title), Some(movement)) => Some(format!("{title} — {movement}")),
+        (title, _) => title.clone(),
+    };
+
+    // The copyright notice comes first, the name of the sequence right after
+    let header = [
+        title.map(|title| midly::MetaMessage::TrackName(text(title))),
+        metadata
+            .copyright
+            .clone()
+            .map(|copyright| midly::MetaMessage::Copyright(text(copyright))),
+    ];
+
+    for meta in header.into_iter().flatten() {
+        tracks.tracks[0].entry(0).or_default().insert(
+            0,
+            midly::TrackEvent {
+                delta: 0.into(),
+                kind: midly::TrackEventKind::Meta(meta),
+            },
+        );
+    }
+
+    let format = if tracks.tracks.len() == 1 {
+        midly::Format::SingleTrack
+    } else {
+        midly::Format::Parallel
+    };
+
+    midly::Smf {
+        header: midly::Header {
+            format,
+            timing: midly::Timing::Metrical(midly::num::u15::new(TICKS_PER_QUARTER_NOTE)),
+        },
+        tracks: tracks.tracks.into_iter().map(track).collect(),
+    }
+}
+
+/// Events of a track with their delta times.
+fn track(events: Events) -> Vec<midly::TrackEvent> {
+    let mut track = vec![];
+
+    let mut prev = 0;
+    for (position, events) in events {
+        let mut delta = position - prev;
+        prev = position;
+
+        for mut event in events {
+            event.delta = (delta as u32).into();
+            track.push(event);
+            delta = 0;
+        }
+    }
+
+    track
+}
+
+pub(crate) fn control_message(control: Control) -> midly::MidiMessage {
+    match control {
+        Control::Program(program) => midly::MidiMessage::ProgramChange {
+            program: program.into(),
+        },
+        Control::Controller { controller, value } => midly::MidiMessage::Controller {
+            controller: controller.into(),
+            value: value.into(),
+        },
+    }
+}
+
+pub(crate) fn push<'a>(events: &mut Events<'a>, position: usize, kind: midly::TrackEventKind<'a>) {
+    events.entry(position).or_default().push(midly::TrackEvent {
+        delta: 0.into(),
+        kind,
+    });
+}
+
+fn note_on(channel: u8, key: u8, vel: u8) -> midly::TrackEventKind<'static> {
+    midly::TrackEventKind::Midi {
+        channel: channel.into(),
+        message: midly::MidiMessage::NoteOn {
+            key: key.into(),
+            vel: vel.into(),
+        },
+    }
+}
+
+fn note_off(channel: u8, key: u8) -> midly::TrackEventKind<'static> {
+    midly::TrackEventKind::Midi {
+        channel: channel.into(),
+        message: midly::MidiMessage::NoteOff {
+            key: key.into(),
+            vel: 0.into(),
+        },
+    }
+}
@@ -3,38 +3,124 @@ use std::fmt;
 use serde::{Deserialize, Deserializer, Serialize, de};
 
 /// https://w3c.github.io/musicxml/musicxml-reference/elements/score-partwise/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct ScorePartwise {
     #[serde(rename = "@version")]
     pub version: Option<String>,
+    pub work: Option<Work>,
+    pub movement_number: Option<String>,
+    pub movement_title: Option<String>,
     pub identification: Option<Identification>,
+    pub defaults: Option<Defaults>,
+    #[serde(default)]
+    pub credit: Vec<Credit>,
     pub part_list: PartList,
     pub part: Vec<Part>,
 }
 
+/// The work a score is part of, when it is a movement of a larger one.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/work/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Work {
+    pub work_number: Option<String>,
+    pub work_title: Option<String>,
+    pub opus: Option<Opus>,
+}
+
+/// A link to an opus document listing the works of the opus.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/opus/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Opus {
+    #[serde(rename = "@xlink:href")]
+    pub href: String,
+}
+
 /// https://w3c.github.io/musicxml/musicxml-reference/elements/identification/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Identification {
     #[serde(default)]
     pub creator: Vec<Creator>,
+    #[serde(default)]
+    pub rights: Vec<Rights>,
     pub encoding: Option<Encoding>,
+    /// Where the music comes from, like the edition it was transcribed from.
+    pub source: Option<String>,
+    #[serde(default)]
+    pub relation: Vec<Relation>,
+    pub miscellaneous: Option<Miscellaneous>,
+}
+
+/// Someone who made the score, their role (like "composer", "lyricist" or "arranger") given
+/// by `type`.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/creator/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Creator {
+    #[serde(rename = "@type")]
+    pub kind: Option<String>,
+    #[serde(default, rename = "$text")]
+    pub value: String,
+}
+
+/// A copyright notice, `type` telling what it covers when the parts of a score differ.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/rights/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rights {
+    #[serde(rename = "@type")]
+    pub kind: Option<String>,
+    #[serde(default, rename = "$text")]
+    pub value: String,
 }
 
-/// https://w3c.github.io/musicxml/musicxml-reference/elements/creator/
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Creator {}
+/// A resource related to the score, `type` being a Dublin Core relation like "isPartOf".
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/relation/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Relation {
+    #[serde(rename = "@type")]
+    pub kind: Option<String>,
+    #[serde(default, rename = "$text")]
+    pub value: String,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/miscellaneous/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Miscellaneous {
+    #[serde(default)]
+    pub miscellaneous_field: Vec<MiscellaneousField>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/miscellaneous-field/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MiscellaneousField {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(default, rename = "$text")]
+    pub value: String,
+}
 
 /// https://w3c.github.io/musicxml/musicxml-reference/elements/encoding/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Encoding {
+    pub encoding_date: Option<String>,
+    #[serde(default)]
+    pub encoder: Vec<Creator>,
+    #[serde(default)]
+    pub software: Vec<String>,
+    pub encoding_description: Option<String>,
     #[serde(default)]
     pub supports: Vec<Supports>,
 }
 
 /// https://w3c.github.io/musicxml/musicxml-reference/elements/supports/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Supports {
     #[serde(rename = "@element")]
     pub element: String,
@@ -46,13 +132,289 @@ pub struct Supports {
     pub value: Option<String>,
 }
 
+/// Score-wide formatting: scaling, page, system and staff layout, and the fonts and line
+/// widths used by default.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/defaults/
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Defaults {
+    pub scaling: Option<Scaling>,
+    /// The score is written at concert pitch.
+    pub concert_score: Option<Empty>,
+    pub page_layout: Option<PageLayout>,
+    pub system_layout: Option<SystemLayout>,
+    #[serde(default)]
+    pub staff_layout: Vec<StaffLayout>,
+    pub appearance: Option<Appearance>,
+    pub music_font: Option<Font>,
+    pub word_font: Option<Font>,
+    #[serde(default)]
+    pub lyric_font: Vec<LyricFont>,
+    #[serde(default)]
+    pub lyric_language: Vec<LyricLanguage>,
+}
+
+/// How big a tenth is: `tenths` tenths are `millimeters` long. A staff space is 10 tenths.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/scaling/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scaling {
+    pub millimeters: f64,
+    pub tenths: Tenths,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/appearance/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Appearance {
+    #[serde(default)]
+    pub line_width: Vec<LineWidth>,
+    #[serde(default)]
+    pub note_size: Vec<NoteSize>,
+    #[serde(default)]
+    pub distance: Vec<Distance>,
+    #[serde(default)]
+    pub glyph: Vec<Glyph>,
+    #[serde(default)]
+    pub other_appearance: Vec<OtherAppearance>,
+}
+
+/// Width of a kind of line, like "stem" or "light barline".
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/line-width/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineWidth {
+    #[serde(rename = "@type")]
+    pub kind: String,
+    #[serde(rename = "$text")]
+    pub value: Tenths,
+}
+
+/// Size of "cue", "grace", "grace-cue" or "large" notes, as a percentage of regular ones.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/note-size/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteSize {
+    #[serde(rename = "@type")]
+    pub kind: String,
+    #[serde(rename = "$text")]
+    pub value: NonNegativeDecimal,
+}
+
+/// A distance between notation elements, like "hyphen" or "beam".
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/distance/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Distance {
+    #[serde(rename = "@type")]
+    pub kind: String,
+    #[serde(rename = "$text")]
+    pub value: Tenths,
+}
+
+/// The SMuFL glyph used for a kind of symbol, like "quarter-rest".
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/glyph/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Glyph {
+    #[serde(rename = "@type")]
+    pub kind: String,
+    #[serde(default, rename = "$text")]
+    pub value: String,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/other-appearance/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OtherAppearance {
+    #[serde(rename = "@type")]
+    pub kind: String,
+    #[serde(default, rename = "$text")]
+    pub value: String,
+}
+
+/// A `<music-font>` or `<word-font>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Font {
+    #[serde(rename = "@font-family")]
+    pub font_family: Option<String>,
+    #[serde(rename = "@font-style")]
+    pub font_style: Option<String>,
+    /// Points, or a CSS size like "large".
+    #[serde(rename = "@font-size")]
+    pub font_size: Option<String>,
+    #[serde(rename = "@font-weight")]
+    pub font_weight: Option<String>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/lyric-font/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LyricFont {
+    #[serde(rename = "@number")]
+    pub number: Option<String>,
+    #[serde(rename = "@name")]
+    pub name: Option<String>,
+    #[serde(rename = "@font-family")]
+    pub font_family: Option<String>,
+    #[serde(rename = "@font-style")]
+    pub font_style: Option<String>,
+    #[serde(rename = "@font-size")]
+    pub font_size: Option<String>,
+    #[serde(rename = "@font-weight")]
+    pub font_weight: Option<String>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/lyric-language/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LyricLanguage {
+    #[serde(rename = "@number")]
+    pub number: Option<String>,
+    #[serde(rename = "@name")]
+    pub name: Option<String>,
+    #[serde(rename = "@xml:lang")]
+    pub lang: String,
+}
+
+/// Text or an image printed on a page outside of the music, like the title or composer.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/credit/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Credit {
+    /// The page the credit is printed on, counting from 1.
+    #[serde(rename = "@page")]
+    pub page: Option<u32>,
+    #[serde(rename = "@id")]
+    pub id: Option<String>,
+    /// What the credit is, like "title", "subtitle", "composer" or "rights".
+    #[serde(default)]
+    pub credit_type: Vec<String>,
+    pub credit_image: Option<Image>,
+    #[serde(default)]
+    pub credit_words: Vec<CreditWords>,
+    #[serde(default)]
+    pub credit_symbol: Vec<CreditSymbol>,
+}
+
+/// Text of a credit, placed in tenths from the bottom left corner of the page.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/credit-words/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreditWords {
+    #[serde(rename = "@default-x")]
+    pub default_x: Option<Tenths>,
+    #[serde(rename = "@default-y")]
+    pub default_y: Option<Tenths>,
+    #[serde(rename = "@justify")]
+    pub justify: Option<String>,
+    #[serde(rename = "@halign")]
+    pub halign: Option<String>,
+    #[serde(rename = "@valign")]
+    pub valign: Option<String>,
+    #[serde(rename = "@font-family")]
+    pub font_family: Option<String>,
+    #[serde(rename = "@font-size")]
+    pub font_size: Option<String>,
+    #[serde(rename = "@font-style")]
+    pub font_style: Option<String>,
+    #[serde(rename = "@font-weight")]
+    pub font_weight: Option<String>,
+    #[serde(rename = "@xml:lang")]
+    pub lang: Option<String>,
+    #[serde(default, rename = "$text")]
+    pub text: String,
+}
+
+/// A SMuFL glyph printed as a credit.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/credit-symbol/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreditSymbol {
+    #[serde(rename = "@default-x")]
+    pub default_x: Option<Tenths>,
+    #[serde(rename = "@default-y")]
+    pub default_y: Option<Tenths>,
+    #[serde(rename = "@justify")]
+    pub justify: Option<String>,
+    #[serde(rename = "@valign")]
+    pub valign: Option<String>,
+    #[serde(rename = "$text")]
+    pub glyph: String,
+}
+
 /// https://w3c.github.io/musicxml/musicxml-reference/elements/part-list/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
-pub struct PartList {}
+pub struct PartList {
+    #[serde(default)]
+    pub score_part: Vec<ScorePart>,
+}
+
+impl PartList {
+    pub fn score_part(&self, id: &str) -> Option<&ScorePart> {
+        self.score_part.iter().find(|part| part.id == id)
+    }
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/score-part/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ScorePart {
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub part_name: String,
+    pub part_abbreviation: Option<String>,
+    #[serde(default)]
+    pub score_instrument: Vec<ScoreInstrument>,
+    #[serde(default)]
+    pub midi_device: Vec<MidiDevice>,
+    #[serde(default)]
+    pub midi_instrument: Vec<MidiInstrument>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/score-instrument/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ScoreInstrument {
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub instrument_name: String,
+    pub instrument_abbreviation: Option<String>,
+    /// Standard instrument sound name, e.g. `keyboard.piano` or `strings.violin`.
+    ///
+    /// Spec: https://www.w3.org/2021/06/musicxml40/listings/sounds.xml/
+    pub instrument_sound: Option<String>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/midi-device/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MidiDevice {
+    #[serde(rename = "@id")]
+    pub id: Option<String>,
+    #[serde(rename = "@port")]
+    pub port: Option<Midi16>,
+    #[serde(default, rename = "$text")]
+    pub name: String,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/midi-instrument/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MidiInstrument {
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub midi_channel: Option<Midi16>,
+    pub midi_name: Option<String>,
+    pub midi_bank: Option<Midi16384>,
+    pub midi_program: Option<Midi128>,
+    pub midi_unpitched: Option<Midi128>,
+    pub volume: Option<Percent>,
+    pub pan: Option<RotationDegrees>,
+    pub elevation: Option<RotationDegrees>,
+}
 
 /// https://w3c.github.io/musicxml/musicxml-reference/elements/part-partwise/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Part {
     #[serde(rename = "@id")]
     pub id: String,
@@ -60,7 +422,7 @@ pub struct Part {
 }
 
 /// https://w3c.github.io/musicxml/musicxml-reference/elements/measure-partwise/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Measure {
     #[serde(rename = "@number")]
     pub number: String,
@@ -79,7 +441,7 @@ pub struct Measure {
     pub content: Vec<MeasureItem>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 #[allow(clippy::large_enum_variant)]
 pub enum MeasureItem {
@@ -89,9 +451,14 @@ pub enum MeasureItem {
     Note(Note),
     Barline(Barline),
     Backup(Backup),
+    Forward(Forward),
     Direction(Direction),
+    Harmony(Harmony),
+    FiguredBass(FiguredBass),
+    Sound(Sound),
 }
 
+#[allow(dead_code)]
 #[derive(Deserialize)]
 struct Abc {
     pub pitch: Option<Pitch>,
@@ -101,6 +468,7 @@ struct Abc {
     pub cba: Cba,
 }
 
+#[allow(dead_code)]
 #[derive(Deserialize)]
 struct Cba {
     pub pitch: Option<Pitch>,
@@ -115,6 +483,7 @@ pub enum NoteKind {
     Regular,
 }
 
+#[allow(unused)]
 fn deserialize_data<'de, D>(deserializer: D) -> Result<Note, D::Error>
 where
     D: Deserializer<'de>,
@@ -185,75 +554,1089 @@ where
     Ok(Note::default())
 }
 
+/// A chord symbol, roman numeral or functional harmony label.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/harmony/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Harmony {
+    /// "explicit", "implied" or "alternate".
+    #[serde(rename = "@type")]
+    pub kind_of_harmony: Option<String>,
+    #[serde(rename = "@print-object")]
+    pub print_object: Option<String>,
+    #[serde(rename = "@print-frame")]
+    pub print_frame: Option<String>,
+    /// "none", "brackets" or "parentheses" around the whole symbol.
+    #[serde(rename = "@arrangement")]
+    pub arrangement: Option<String>,
+    #[serde(rename = "@placement")]
+    pub placement: Option<String>,
+    pub root: Option<Root>,
+    pub numeral: Option<Numeral>,
+    pub function: Option<StyleText>,
+    pub kind: Kind,
+    pub inversion: Option<Inversion>,
+    pub bass: Option<Bass>,
+    #[serde(default)]
+    pub degree: Vec<Degree>,
+    pub frame: Option<Frame>,
+    pub offset: Option<Offset>,
+    pub staff: Option<StaffNumber>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/root/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Root {
+    pub root_step: HarmonyStep,
+    pub root_alter: Option<HarmonyAlter>,
+}
+
+/// A `<root-step>` or `<bass-step>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarmonyStep {
+    /// How the step is displayed, when it differs from its letter.
+    #[serde(rename = "@text")]
+    pub text: Option<String>,
+    #[serde(rename = "$text")]
+    pub value: Step,
+}
+
+/// A `<root-alter>`, `<bass-alter>` or `<numeral-alter>` in semitones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarmonyAlter {
+    #[serde(rename = "@print-object")]
+    pub print_object: Option<String>,
+    /// "left" or "right" of the step.
+    #[serde(rename = "@location")]
+    pub location: Option<String>,
+    #[serde(rename = "$text")]
+    pub value: Semitones,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/numeral/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Numeral {
+    pub numeral_root: NumeralRoot,
+    pub numeral_alter: Option<HarmonyAlter>,
+    pub numeral_key: Option<NumeralKey>,
+}
+
+/// Scale degree of the root, 1 to 7.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NumeralRoot {
+    #[serde(rename = "@text")]
+    pub text: Option<String>,
+    #[serde(rename = "$text")]
+    pub value: u8,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/numeral-key/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NumeralKey {
+    pub numeral_fifths: i8,
+    /// "major", "minor", "natural minor", "melodic minor" or "harmonic minor".
+    pub numeral_mode: String,
+}
+
+/// Text written in a given style, like the `<function>` of a harmony.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StyleText {
+    #[serde(default, rename = "$text")]
+    pub value: String,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/kind/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Kind {
+    /// How the kind is displayed, like "m7" or "maj7".
+    #[serde(rename = "@text")]
+    pub text: Option<String>,
+    #[serde(rename = "@use-symbols")]
+    pub use_symbols: Option<String>,
+    #[serde(rename = "@stack-degrees")]
+    pub stack_degrees: Option<String>,
+    #[serde(rename = "@parentheses-degrees")]
+    pub parentheses_degrees: Option<String>,
+    #[serde(rename = "@bracket-degrees")]
+    pub bracket_degrees: Option<String>,
+    #[serde(rename = "$text")]
+    pub value: KindValue,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/data-types/kind-value/
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum KindValue {
+    Major,
+    Minor,
+    Augmented,
+    Diminished,
+    Dominant,
+    MajorSeventh,
+    MinorSeventh,
+    DiminishedSeventh,
+    AugmentedSeventh,
+    HalfDiminished,
+    MajorMinor,
+    MajorSixth,
+    MinorSixth,
+    DominantNinth,
+    MajorNinth,
+    MinorNinth,
+    #[serde(rename = "dominant-11th")]
+    Dominant11th,
+    #[serde(rename = "major-11th")]
+    Major11th,
+    #[serde(rename = "minor-11th")]
+    Minor11th,
+    #[serde(rename = "dominant-13th")]
+    Dominant13th,
+    #[serde(rename = "major-13th")]
+    Major13th,
+    #[serde(rename = "minor-13th")]
+    Minor13th,
+    SuspendedSecond,
+    SuspendedFourth,
+    #[serde(rename = "Neapolitan")]
+    Neapolitan,
+    #[serde(rename = "Italian")]
+    Italian,
+    #[serde(rename = "French")]
+    French,
+    #[serde(rename = "German")]
+    German,
+    Pedal,
+    Power,
+    #[serde(rename = "Tristan")]
+    Tristan,
+    Other,
+    /// No chord, "N.C.".
+    None,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/inversion/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Inversion {
+    #[serde(rename = "@text")]
+    pub text: Option<String>,
+    /// 0 for root position, 1 for first inversion and so on.
+    #[serde(rename = "$text")]
+    pub value: u8,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/bass/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Bass {
+    /// "horizontal", "vertical" or "diagonal".
+    #[serde(rename = "@arrangement")]
+    pub arrangement: Option<String>,
+    pub bass_separator: Option<StyleText>,
+    pub bass_step: HarmonyStep,
+    pub bass_alter: Option<HarmonyAlter>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/degree/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Degree {
+    #[serde(rename = "@print-object")]
+    pub print_object: Option<String>,
+    pub degree_value: DegreeValue,
+    pub degree_alter: DegreeAlter,
+    pub degree_type: DegreeType,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/degree-value/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DegreeValue {
+    #[serde(rename = "@symbol")]
+    pub symbol: Option<String>,
+    #[serde(rename = "@text")]
+    pub text: Option<String>,
+    /// Degree counted from the root of a dominant chord, so 7 is a minor seventh.
+    #[serde(rename = "$text")]
+    pub value: u8,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/degree-alter/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DegreeAlter {
+    #[serde(rename = "@plus-minus")]
+    pub plus_minus: Option<String>,
+    #[serde(rename = "$text")]
+    pub value: Semitones,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/degree-type/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DegreeType {
+    #[serde(rename = "@text")]
+    pub text: Option<String>,
+    #[serde(rename = "$text")]
+    pub value: DegreeTypeValue,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/data-types/degree-type-value/
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum DegreeTypeValue {
+    Add,
+    Alter,
+    Subtract,
+}
+
+/// A chord diagram for fretted instruments.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/frame/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Frame {
+    pub frame_strings: u8,
+    pub frame_frets: u8,
+    pub first_fret: Option<FirstFret>,
+    #[serde(default)]
+    pub frame_note: Vec<FrameNote>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/first-fret/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirstFret {
+    #[serde(rename = "@text")]
+    pub text: Option<String>,
+    #[serde(rename = "@location")]
+    pub location: Option<String>,
+    #[serde(rename = "$text")]
+    pub value: u8,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/frame-note/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameNote {
+    /// Counted from the lowest string, starting at 1.
+    pub string: u8,
+    /// 0 for an open string.
+    pub fret: u8,
+    pub fingering: Option<Fingering>,
+    pub barre: Option<Barre>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/barre/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Barre {
+    #[serde(rename = "@type")]
+    pub kind: StartStop,
+}
+
+/// Figures written under a bass note, telling the continuo player which chord to play.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/figured-bass/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FiguredBass {
+    #[serde(rename = "@parentheses")]
+    pub parentheses: Option<String>,
+    #[serde(rename = "@print-object")]
+    pub print_object: Option<String>,
+    #[serde(default)]
+    pub figure: Vec<Figure>,
+    /// How long the figures last when a note has more than one set of them, in divisions.
+    pub duration: Option<String>,
+    pub offset: Option<Offset>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/figure/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Figure {
+    /// An accidental before the number: "sharp", "flat", "natural", "double-sharp",
+    /// "flat-flat" or "sharp-sharp".
+    pub prefix: Option<StyleText>,
+    pub figure_number: Option<StyleText>,
+    /// An accidental after the number, or "slash", "back-slash" or "vertical" through it
+    /// for a raised interval.
+    pub suffix: Option<StyleText>,
+    /// The figure of the previous note is held.
+    pub extend: Option<Extend>,
+}
+
 /// https://www.w3.org/2021/06/musicxml40/musicxml-reference/elements/direction/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Direction {
+    #[serde(rename = "@placement")]
+    pub placement: Option<String>,
+    #[serde(default)]
+    pub direction_type: Vec<DirectionType>,
+    /// Where the direction sits relative to the current position, in divisions.
+    pub offset: Option<Offset>,
+    pub voice: Option<String>,
+    pub staff: Option<StaffNumber>,
     pub sound: Option<Sound>,
 }
 
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/direction-type/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DirectionType {
+    #[serde(default)]
+    pub rehearsal: Vec<FormattedText>,
+    #[serde(default)]
+    pub segno: Vec<Segno>,
+    #[serde(default)]
+    pub coda: Vec<Coda>,
+    #[serde(default)]
+    pub words: Vec<FormattedText>,
+    /// A SMuFL glyph name.
+    #[serde(default)]
+    pub symbol: Vec<FormattedText>,
+    pub wedge: Option<Wedge>,
+    #[serde(default)]
+    pub dynamics: Vec<Dynamics>,
+    pub dashes: Option<Dashes>,
+    pub bracket: Option<Bracket>,
+    pub pedal: Option<Pedal>,
+    pub metronome: Option<Metronome>,
+    pub octave_shift: Option<OctaveShift>,
+    pub harp_pedals: Option<HarpPedals>,
+    pub damp: Option<Empty>,
+    pub damp_all: Option<Empty>,
+    pub eyeglasses: Option<Empty>,
+    pub string_mute: Option<StringMute>,
+    pub scordatura: Option<Scordatura>,
+    pub image: Option<Image>,
+    pub principal_voice: Option<PrincipalVoice>,
+    #[serde(default)]
+    pub percussion: Vec<Percussion>,
+    pub accordion_registration: Option<AccordionRegistration>,
+    pub staff_divide: Option<StaffDivide>,
+    pub other_direction: Option<FormattedText>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/segno/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Segno {
+    #[serde(rename = "@smufl")]
+    pub smufl: Option<String>,
+    #[serde(rename = "@id")]
+    pub id: Option<String>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/coda/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Coda {
+    #[serde(rename = "@smufl")]
+    pub smufl: Option<String>,
+    #[serde(rename = "@id")]
+    pub id: Option<String>,
+}
+
+/// A crescendo or diminuendo hairpin.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/wedge/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Wedge {
+    #[serde(rename = "@type")]
+    pub kind: WedgeType,
+    #[serde(rename = "@number")]
+    pub number: Option<NumberLevel>,
+    #[serde(rename = "@niente")]
+    pub niente: Option<String>,
+    /// Distance between the lines at the open end, in tenths.
+    #[serde(rename = "@spread")]
+    pub spread: Option<f64>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/data-types/wedge-type/
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum WedgeType {
+    Crescendo,
+    Diminuendo,
+    Stop,
+    Continue,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/dynamics/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dynamics {
+    #[serde(rename = "@placement")]
+    pub placement: Option<String>,
+    #[serde(default, rename = "$value")]
+    pub marks: Vec<DynamicsMark>,
+}
+
+/// A single mark of a `<dynamics>`, like `pp` or `sfz`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DynamicsMark {
+    P,
+    Pp,
+    Ppp,
+    Pppp,
+    Ppppp,
+    Pppppp,
+    F,
+    Ff,
+    Fff,
+    Ffff,
+    Fffff,
+    Ffffff,
+    Mp,
+    Mf,
+    Sf,
+    Sfp,
+    Sfpp,
+    Fp,
+    Rf,
+    Rfz,
+    Sfz,
+    Sffz,
+    Fz,
+    N,
+    Pf,
+    Sfzp,
+    OtherDynamics(String),
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/bracket/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bracket {
+    #[serde(rename = "@type")]
+    pub kind: StartStopContinue,
+    #[serde(rename = "@number")]
+    pub number: Option<NumberLevel>,
+    /// "up", "down", "both", "arrow" or "none".
+    #[serde(rename = "@line-end")]
+    pub line_end: Option<String>,
+    #[serde(rename = "@line-type")]
+    pub line_type: Option<String>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/pedal/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pedal {
+    /// "start", "stop", "sostenuto", "change", "continue", "discontinue" or "resume".
+    #[serde(rename = "@type")]
+    pub kind: String,
+    #[serde(rename = "@number")]
+    pub number: Option<NumberLevel>,
+    #[serde(rename = "@line")]
+    pub line: Option<String>,
+    #[serde(rename = "@sign")]
+    pub sign: Option<String>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/harp-pedals/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct HarpPedals {
+    #[serde(default)]
+    pub pedal_tuning: Vec<PedalTuning>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/pedal-tuning/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PedalTuning {
+    pub pedal_step: String,
+    pub pedal_alter: f64,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/string-mute/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StringMute {
+    /// "on" or "off".
+    #[serde(rename = "@type")]
+    pub kind: String,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/scordatura/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scordatura {
+    #[serde(default)]
+    pub accord: Vec<Accord>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/accord/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Accord {
+    #[serde(rename = "@string")]
+    pub string: Option<u8>,
+    pub tuning_step: String,
+    pub tuning_alter: Option<f64>,
+    pub tuning_octave: u8,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/image/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Image {
+    #[serde(rename = "@source")]
+    pub source: String,
+    /// MIME type of the image.
+    #[serde(rename = "@type")]
+    pub kind: String,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/principal-voice/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrincipalVoice {
+    #[serde(rename = "@type")]
+    pub kind: StartStop,
+    /// "Hauptstimme", "Nebenstimme", "plain" or "none".
+    #[serde(rename = "@symbol")]
+    pub symbol: String,
+    #[serde(default, rename = "$text")]
+    pub text: String,
+}
+
+/// A percussion pictogram, the instrument itself is not modelled.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/percussion/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Percussion {
+    #[serde(rename = "@enclosure")]
+    pub enclosure: Option<String>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/accordion-registration/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AccordionRegistration {
+    pub accordion_high: Option<Empty>,
+    /// Number of dots in the middle section, 1 to 3.
+    pub accordion_middle: Option<u8>,
+    pub accordion_low: Option<Empty>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/staff-divide/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaffDivide {
+    /// "down", "up" or "up-down".
+    #[serde(rename = "@type")]
+    pub kind: String,
+}
+
+/// Dashed lines, extending a text direction like "cresc." or "rit." over a span of music.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/dashes/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dashes {
+    #[serde(rename = "@type")]
+    pub kind: StartStopContinue,
+    #[serde(rename = "@number")]
+    pub number: Option<NumberLevel>,
+}
+
+/// Text along with its formatting, as used by `<words>` and friends.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/data-types/formatted-text-id/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormattedText {
+    #[serde(rename = "@enclosure")]
+    pub enclosure: Option<String>,
+    #[serde(rename = "@xml:lang")]
+    pub lang: Option<String>,
+    #[serde(default, rename = "$text")]
+    pub text: String,
+}
+
+/// A metronome mark, either `beat-unit = per-minute` or a metric modulation
+/// `beat-unit = beat-unit`.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/metronome/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Metronome {
+    #[serde(rename = "@parentheses")]
+    pub parentheses: Option<String>,
+    /// Kept in document order, since beat-unit dots belong to the beat-unit before them.
+    #[serde(rename = "$value")]
+    pub content: Vec<MetronomeContent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MetronomeContent {
+    BeatUnit(BeatUnit),
+    BeatUnitDot,
+    BeatUnitTied(BeatUnitTied),
+    PerMinute(String),
+    MetronomeArrows,
+    MetronomeNote(MetronomeNote),
+    MetronomeRelation(String),
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/beat-unit/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeatUnit {
+    #[serde(rename = "$text")]
+    pub value: NoteTypeValue,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/beat-unit-tied/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BeatUnitTied {
+    pub beat_unit: BeatUnit,
+    #[serde(default)]
+    pub beat_unit_dot: Vec<Empty>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/metronome-note/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetronomeNote {
+    pub metronome_type: NoteTypeValue,
+    #[serde(default)]
+    pub metronome_dot: Vec<Empty>,
+}
+
+/// An element without content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Empty {}
+
+/// An 8va, 8vb, 15ma or 15mb line.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/octave-shift/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OctaveShift {
+    /// Direction in which the notes are *written* relative to their sounding pitch,
+    /// so an 8va is a shift `down`.
+    #[serde(rename = "@type")]
+    pub kind: UpDownStopContinue,
+    #[serde(rename = "@number")]
+    pub number: Option<NumberLevel>,
+    /// 8 for one octave, 15 for two and 22 for three.
+    #[serde(rename = "@size")]
+    pub size: Option<u8>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/data-types/up-down-stop-continue/
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum UpDownStopContinue {
+    Up,
+    Down,
+    Stop,
+    Continue,
+}
+
 /// https://w3c.github.io/musicxml/musicxml-reference/elements/sound/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Sound {
     #[serde(rename = "@tempo")]
     pub tempo: Option<String>,
+    /// Loudness as a percentage of the default forte (MIDI velocity 90).
+    #[serde(rename = "@dynamics")]
+    pub dynamics: Option<NonNegativeDecimal>,
+    #[serde(rename = "@dacapo")]
+    pub dacapo: Option<String>,
+    #[serde(rename = "@segno")]
+    pub segno: Option<String>,
+    #[serde(rename = "@dalsegno")]
+    pub dalsegno: Option<String>,
+    #[serde(rename = "@coda")]
+    pub coda: Option<String>,
+    #[serde(rename = "@tocoda")]
+    pub tocoda: Option<String>,
+    #[serde(rename = "@divisions")]
+    pub divisions: Option<Divisions>,
+    #[serde(rename = "@forward-repeat")]
+    pub forward_repeat: Option<String>,
+    #[serde(rename = "@fine")]
+    pub fine: Option<String>,
+    /// Comma separated list of the times through a repeat the sound applies to.
+    #[serde(rename = "@time-only")]
+    pub time_only: Option<String>,
+    #[serde(rename = "@pizzicato")]
+    pub pizzicato: Option<String>,
+    #[serde(rename = "@pan")]
+    pub pan: Option<RotationDegrees>,
+    #[serde(rename = "@elevation")]
+    pub elevation: Option<RotationDegrees>,
+    /// "yes", "no" or the pedal depression in percent.
+    #[serde(rename = "@damper-pedal")]
+    pub damper_pedal: Option<String>,
+    #[serde(rename = "@soft-pedal")]
+    pub soft_pedal: Option<String>,
+    #[serde(rename = "@sostenuto-pedal")]
+    pub sostenuto_pedal: Option<String>,
+    #[serde(rename = "@id")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub instrument_change: Vec<InstrumentChange>,
+    #[serde(default)]
+    pub midi_device: Vec<MidiDevice>,
+    #[serde(default)]
+    pub midi_instrument: Vec<MidiInstrument>,
+    #[serde(default)]
+    pub play: Vec<Play>,
+    pub swing: Option<Swing>,
+    pub offset: Option<Offset>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/instrument-change/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct InstrumentChange {
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub instrument_sound: Option<String>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/play/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Play {
+    #[serde(rename = "@id")]
+    pub id: Option<String>,
+    pub ipa: Option<String>,
+    pub mute: Option<Mute>,
+    pub semi_pitched: Option<String>,
+    pub other_play: Option<OtherPlay>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/data-types/mute/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Mute {
+    On,
+    Off,
+    Straight,
+    Cup,
+    HarmonNoStem,
+    HarmonStem,
+    Bucket,
+    Plunger,
+    Hat,
+    Solotone,
+    Practice,
+    StopMute,
+    StopHand,
+    Echo,
+    Palm,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/other-play/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OtherPlay {
+    #[serde(rename = "@type")]
+    pub kind: String,
+    #[serde(default, rename = "$text")]
+    pub text: String,
+}
+
+/// Distance of a direction or sound from its position in the measure, in divisions.
+///
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/offset/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Offset {
+    #[serde(rename = "@sound")]
+    pub sound: Option<String>,
+    #[serde(rename = "$text")]
+    pub value: Divisions,
+}
+
+/// Swing playback, either `<straight/>` or the `first`:`second` ratio of a pair of
+/// `swing-type` notes (eighths by default).
+///
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/swing/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Swing {
+    pub straight: Option<Empty>,
+    pub first: Option<u32>,
+    pub second: Option<u32>,
+    pub swing_type: Option<NoteTypeValue>,
+    pub swing_style: Option<String>,
+}
+
+/// Layout changes and page or system breaks, starting with the measure it is in.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/print/
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Print {
-    pub system_layout: SystemLayout,
+    /// Distance between the staves of the system, overriding `<staff-layout>`.
+    #[serde(rename = "@staff-spacing")]
+    pub staff_spacing: Option<Tenths>,
+    #[serde(rename = "@new-system")]
+    pub new_system: Option<String>,
+    #[serde(rename = "@new-page")]
+    pub new_page: Option<String>,
+    /// Number of empty pages inserted before the measure, ignored unless it starts a new page.
+    #[serde(rename = "@blank-page")]
+    pub blank_page: Option<u32>,
+    /// Number printed on the page, ignored unless it starts a new page.
+    #[serde(rename = "@page-number")]
+    pub page_number: Option<String>,
+    #[serde(rename = "@id")]
+    pub id: Option<String>,
+    pub page_layout: Option<PageLayout>,
+    pub system_layout: Option<SystemLayout>,
+    #[serde(default)]
+    pub staff_layout: Vec<StaffLayout>,
+    pub measure_layout: Option<MeasureLayout>,
+    pub measure_numbering: Option<MeasureNumbering>,
+    pub part_name_display: Option<NameDisplay>,
+    pub part_abbreviation_display: Option<NameDisplay>,
+}
+
+impl Print {
+    pub fn is_new_system(&self) -> bool {
+        self.new_system.as_deref() == Some("yes") || self.is_new_page()
+    }
+
+    pub fn is_new_page(&self) -> bool {
+        self.new_page.as_deref() == Some("yes")
+    }
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/measure-layout/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MeasureLayout {
+    /// Distance from the previous measure, for measures that do not start a system.
+    pub measure_distance: Option<Tenths>,
+}
+
+/// Which measure numbers are shown.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/measure-numbering/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeasureNumbering {
+    /// "system" or "score".
+    #[serde(rename = "@system")]
+    pub system: Option<String>,
+    #[serde(rename = "@staff")]
+    pub staff: Option<StaffNumber>,
+    #[serde(rename = "@multiple-rest-always")]
+    pub multiple_rest_always: Option<String>,
+    #[serde(rename = "@multiple-rest-range")]
+    pub multiple_rest_range: Option<String>,
+    /// "none", "measure" for every measure or "system" for the first of each system.
+    #[serde(rename = "$text")]
+    pub value: String,
+}
+
+/// A `<part-name-display>` or `<part-abbreviation-display>`, the name as it is printed.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/part-name-display/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NameDisplay {
+    #[serde(rename = "@print-object")]
+    pub print_object: Option<String>,
+    #[serde(default)]
+    pub display_text: Vec<FormattedText>,
+    /// Accidentals within the name, like the flat of "Clarinet in B♭".
+    #[serde(default)]
+    pub accidental_text: Vec<FormattedText>,
 }
 
 /// https://w3c.github.io/musicxml/musicxml-reference/elements/system-layout/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct SystemLayout {
     pub system_margins: Option<SystemMargins>,
-    pub top_system_distance: Option<String>,
+    /// Distance from the bottom line of the previous system to the top line of this one.
+    pub system_distance: Option<Tenths>,
+    /// Distance from the top margin of the page to the top line of its first system.
+    pub top_system_distance: Option<Tenths>,
+    pub system_dividers: Option<SystemDividers>,
 }
 
-/// https://w3c.github.io/musicxml/musicxml-reference/elements/system-margins/
-#[derive(Debug, Serialize, Deserialize)]
+/// Margins of a system, relative to the margins of the page.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/system-margins/
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct SystemMargins {
-    pub left_margin: String,
-    pub right_margin: String,
+    pub left_margin: Tenths,
+    pub right_margin: Tenths,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/system-dividers/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SystemDividers {
+    pub left_divider: Option<Divider>,
+    pub right_divider: Option<Divider>,
+}
+
+/// A `<left-divider>` or `<right-divider>` between systems.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Divider {
+    #[serde(rename = "@print-object")]
+    pub print_object: Option<String>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/staff-layout/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct StaffLayout {
+    /// The staff of a multi-staff part, every staff when missing.
+    #[serde(rename = "@number")]
+    pub number: Option<StaffNumber>,
+    /// Distance from the bottom line of the previous staff to the top line of this one.
+    pub staff_distance: Option<Tenths>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/page-layout/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PageLayout {
+    pub page_height: Option<Tenths>,
+    pub page_width: Option<Tenths>,
+    /// Up to two sets of margins, for odd and even pages.
+    #[serde(default)]
+    pub page_margins: Vec<PageMargins>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/page-margins/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PageMargins {
+    /// The pages the margins apply to, all of them when missing.
+    #[serde(rename = "@type")]
+    pub kind: Option<MarginType>,
+    pub left_margin: Tenths,
+    pub right_margin: Tenths,
+    pub top_margin: Tenths,
+    pub bottom_margin: Tenths,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/data-types/margin-type/
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum MarginType {
+    Odd,
+    Even,
+    Both,
 }
 
 /// https://w3c.github.io/musicxml/musicxml-reference/elements/attributes/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub struct Attributes {
     pub divisions: Option<String>,
     #[serde(default)]
     pub key: Vec<Key>,
     #[serde(default)]
     pub time: Vec<Time>,
+    /// Number of staves of the part, 1 if missing.
+    pub staves: Option<u8>,
     #[serde(default)]
     pub clef: Vec<Clef>,
+    #[serde(default)]
+    pub transpose: Vec<Transpose>,
+    #[serde(default)]
+    pub measure_style: Vec<MeasureStyle>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/measure-style/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MeasureStyle {
+    #[serde(rename = "@number")]
+    pub number: Option<StaffNumber>,
+    pub multiple_rest: Option<MultipleRest>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// A run of empty measures displayed as a single rest, starting with the measure it is
+/// given in.
+///
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/multiple-rest/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultipleRest {
+    #[serde(rename = "@use-symbols")]
+    pub use_symbols: Option<String>,
+    /// Number of measures.
+    #[serde(rename = "$text")]
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Key {
     pub fifths: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct Time {
     pub beats: String,
     pub beat_type: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Written pitch of a transposing instrument, relative to its sounding pitch.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/transpose/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Transpose {
+    /// The staff this transposition applies to, all staves of the part if missing.
+    #[serde(rename = "@number")]
+    pub number: Option<StaffNumber>,
+    #[serde(rename = "@id")]
+    pub id: Option<String>,
+    /// Number of diatonic steps added to the written pitch.
+    pub diatonic: Option<i32>,
+    /// Number of semitones added to the written pitch.
+    pub chromatic: Semitones,
+    /// Number of octaves added to the written pitch.
+    pub octave_change: Option<i32>,
+    pub double: Option<Double>,
+}
+
+/// Music doubled one octave below (or above) what is written.
+///
+/// Spec: https://w3c.github.io/musicxml/musicxml-reference/elements/double/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Double {
+    #[serde(rename = "@above")]
+    pub above: Option<String>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/clef/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub struct Clef {
-    pub sign: String,
-    pub line: String,
+    /// The staff this clef applies to, 1 if missing.
+    #[serde(rename = "@number")]
+    pub number: Option<StaffNumber>,
+    #[serde(rename = "@additional")]
+    pub additional: Option<String>,
+    pub sign: ClefSign,
+    /// The staff line of the sign, counted from the bottom.
+    pub line: Option<StaffLine>,
+    /// Octave transposition of the clef, e.g. -1 for the treble clef with an 8 below.
+    pub clef_octave_change: Option<i32>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/data-types/clef-sign/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ClefSign {
+    G,
+    F,
+    C,
+    #[serde(rename = "percussion")]
+    Percussion,
+    #[serde(rename = "TAB")]
+    Tab,
+    #[serde(rename = "jianpu")]
+    Jianpu,
+    #[serde(rename = "none")]
+    None,
 }
 
 /// https://w3c.github.io/musicxml/musicxml-reference/elements/note/
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Note {
     #[serde(rename = "@attack")]
@@ -272,18 +1655,166 @@ pub struct Note {
     // ... more attributes
     // ... There are multiple note types
     pub pitch: Option<Pitch>,
+    pub unpitched: Option<Unpitched>,
     pub chord: Option<Chord>,
     pub duration: String,
+    #[serde(default)]
+    pub instrument: Vec<Instrument>,
     pub voice: Option<String>,
     #[serde(rename = "type")]
     pub kind: Option<String>,
+    pub accidental: Option<Accidental>,
     pub stem: Option<String>,
+    pub staff: Option<StaffNumber>,
     pub rest: Option<Rest>,
-    pub tie: Option<Tie>,
+    #[serde(default)]
+    pub tie: Vec<Tie>,
+    #[serde(default)]
+    pub notations: Vec<Notations>,
+    #[serde(default)]
+    pub lyric: Vec<Lyric>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/lyric/
+///
+/// Syllables joined by an elision are sung on the same note, `elision[i]` sits between
+/// `text[i]` and `text[i + 1]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Lyric {
+    /// The verse, "1" when missing.
+    #[serde(rename = "@number")]
+    pub number: Option<String>,
+    #[serde(rename = "@name")]
+    pub name: Option<String>,
+    #[serde(rename = "@placement")]
+    pub placement: Option<String>,
+    #[serde(default)]
+    pub syllabic: Vec<Syllabic>,
+    #[serde(default)]
+    pub text: Vec<TextElementData>,
+    #[serde(default)]
+    pub elision: Vec<Elision>,
+    pub extend: Option<Extend>,
+    pub laughing: Option<Empty>,
+    pub humming: Option<Empty>,
+    pub end_line: Option<Empty>,
+    pub end_paragraph: Option<Empty>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/syllabic/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Syllabic {
+    #[serde(rename = "$text")]
+    pub value: SyllabicValue,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/data-types/syllabic/
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyllabicValue {
+    /// A whole word.
+    #[default]
+    Single,
+    Begin,
+    Middle,
+    End,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/text/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextElementData {
+    #[serde(rename = "@xml:lang")]
+    pub lang: Option<String>,
+    #[serde(default, rename = "$text")]
+    pub value: String,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/elision/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Elision {
+    /// The symbol joining the syllables, usually an undertie.
+    #[serde(default, rename = "$text")]
+    pub value: String,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/extend/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Extend {
+    #[serde(rename = "@type")]
+    pub kind: Option<StartStopContinue>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/notations/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notations {
+    #[serde(default)]
+    pub glissando: Vec<Glissando>,
+    #[serde(default)]
+    pub slide: Vec<Slide>,
+    #[serde(default)]
+    pub technical: Vec<Technical>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/technical/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Technical {
+    #[serde(default)]
+    pub fingering: Vec<Fingering>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/fingering/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fingering {
+    /// The finger replaces the previous one while the note is held.
+    #[serde(rename = "@substitution")]
+    pub substitution: Option<String>,
+    /// The finger may be used instead of the previous one.
+    #[serde(rename = "@alternate")]
+    pub alternate: Option<String>,
+    #[serde(rename = "@placement")]
+    pub placement: Option<String>,
+    #[serde(default, rename = "$text")]
+    pub finger: String,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/glissando/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Glissando {
+    #[serde(rename = "@type")]
+    pub kind: StartStop,
+    #[serde(rename = "@number")]
+    pub number: Option<NumberLevel>,
+    #[serde(rename = "@line-type")]
+    pub line_type: Option<String>,
+    #[serde(default, rename = "$text")]
+    pub text: String,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/slide/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Slide {
+    #[serde(rename = "@type")]
+    pub kind: StartStop,
+    #[serde(rename = "@number")]
+    pub number: Option<NumberLevel>,
+    #[serde(rename = "@line-type")]
+    pub line_type: Option<String>,
+    /// Whether the bend should accelerate towards its end.
+    #[serde(rename = "@accelerate")]
+    pub accelerate: Option<String>,
+    #[serde(rename = "@beats")]
+    pub beats: Option<TrillBeats>,
+    #[serde(rename = "@first-beat")]
+    pub first_beat: Option<Percent>,
+    #[serde(rename = "@last-beat")]
+    pub last_beat: Option<Percent>,
+    #[serde(default, rename = "$text")]
+    pub text: String,
 }
 
 /// https://w3c.github.io/musicxml/musicxml-reference/elements/tie/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tie {
     #[serde(rename = "@type")]
     pub kind: StartStop,
@@ -299,39 +1830,203 @@ pub enum StartStop {
     Stop,
 }
 
+/// https://w3c.github.io/musicxml/musicxml-reference/data-types/start-stop-continue/
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum StartStopContinue {
+    Start,
+    Stop,
+    Continue,
+}
+
 /// https://w3c.github.io/musicxml/musicxml-reference/elements/pitch/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Pitch {
     pub step: Step,
     pub alter: Option<Semitones>,
     pub octave: Octave,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/unpitched/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Unpitched {
+    pub display_step: Option<Step>,
+    pub display_octave: Option<Octave>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/instrument/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Instrument {
+    /// Refers to a `<score-instrument>` of the part.
+    #[serde(rename = "@id")]
+    pub id: String,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/accidental/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Accidental {
+    #[serde(rename = "@cautionary")]
+    pub cautionary: Option<String>,
+    #[serde(rename = "@editorial")]
+    pub editorial: Option<String>,
+    #[serde(rename = "@parentheses")]
+    pub parentheses: Option<String>,
+    #[serde(rename = "@smufl")]
+    pub smufl: Option<String>,
+    #[serde(rename = "$text")]
+    pub value: AccidentalValue,
+}
+
+impl Accidental {
+    /// Sounding alteration of quarter-tone accidentals, in semitones.
+    ///
+    /// Accidentals without a well established microtonal meaning return `None`,
+    /// their alteration has to come from `<alter>`.
+    pub fn microtonal_alter(&self) -> Option<Semitones> {
+        use AccidentalValue::*;
+        let alter = match self.value {
+            QuarterFlat | NaturalDown | FlatUp | Koron => -0.5,
+            QuarterSharp | NaturalUp | SharpDown | SlashQuarterSharp | Sori => 0.5,
+            ThreeQuartersFlat | FlatDown | FlatFlatUp => -1.5,
+            ThreeQuartersSharp | SharpUp | DoubleSharpDown => 1.5,
+            FlatFlatDown => -2.5,
+            DoubleSharpUp => 2.5,
+            _ => return None,
+        };
+        Some(alter)
+    }
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/data-types/accidental-value/
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum AccidentalValue {
+    Sharp,
+    Natural,
+    Flat,
+    DoubleSharp,
+    SharpSharp,
+    FlatFlat,
+    NaturalSharp,
+    NaturalFlat,
+    QuarterFlat,
+    QuarterSharp,
+    ThreeQuartersFlat,
+    ThreeQuartersSharp,
+    SharpDown,
+    SharpUp,
+    NaturalDown,
+    NaturalUp,
+    FlatDown,
+    FlatUp,
+    DoubleSharpDown,
+    DoubleSharpUp,
+    FlatFlatDown,
+    FlatFlatUp,
+    ArrowDown,
+    ArrowUp,
+    TripleSharp,
+    TripleFlat,
+    SlashQuarterSharp,
+    SlashSharp,
+    SlashFlat,
+    DoubleSlashFlat,
+    #[serde(rename = "sharp-1")]
+    Sharp1,
+    #[serde(rename = "sharp-2")]
+    Sharp2,
+    #[serde(rename = "sharp-3")]
+    Sharp3,
+    #[serde(rename = "sharp-5")]
+    Sharp5,
+    #[serde(rename = "flat-1")]
+    Flat1,
+    #[serde(rename = "flat-2")]
+    Flat2,
+    #[serde(rename = "flat-3")]
+    Flat3,
+    #[serde(rename = "flat-4")]
+    Flat4,
+    Sori,
+    Koron,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Chord {}
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Rest {
     #[serde(rename = "@measure")]
     pub measure: Option<String>,
 }
 
 /// https://w3c.github.io/musicxml/musicxml-reference/elements/barline/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Barline {
     #[serde(rename = "@location")]
     pub location: Option<String>,
     pub bar_style: Option<String>,
+    pub ending: Option<Ending>,
+    pub repeat: Option<Repeat>,
+}
+
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/repeat/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Repeat {
+    #[serde(rename = "@direction")]
+    pub direction: BackwardForward,
+    /// How many times the repeated section is played, 2 if missing.
+    #[serde(rename = "@times")]
+    pub times: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackwardForward {
+    Backward,
+    Forward,
+}
+
+/// A volta bracket, played only on the times through the repeat listed in `number`.
+///
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/ending/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ending {
+    /// Comma separated list of times through the repeat, like "1, 2".
+    #[serde(rename = "@number")]
+    pub number: String,
+    #[serde(rename = "@type")]
+    pub kind: StartStopDiscontinue,
+    #[serde(default, rename = "$text")]
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StartStopDiscontinue {
+    Start,
+    Stop,
+    Discontinue,
 }
 
 /// https://w3c.github.io/musicxml/musicxml-reference/elements/backup/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Backup {
     pub duration: String,
 }
 
+/// https://w3c.github.io/musicxml/musicxml-reference/elements/forward/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Forward {
+    pub duration: String,
+    pub voice: Option<String>,
+    pub staff: Option<StaffNumber>,
+}
+
 pub use primitive::*;
 mod primitive {
     use super::*;
@@ -406,6 +2101,18 @@ mod primitive {
     /// Spec: https://www.w3.org/2021/06/musicxml40/musicxml-reference/data-types/number-level/
     pub type NumberLevel = u8;
 
+    /// The `staff-number` type indicates staff numbers within a multi-staff part.
+    /// Staves are numbered from top to bottom, with 1 being the top staff on a part.
+    ///
+    /// Spec: https://www.w3.org/2021/06/musicxml40/musicxml-reference/data-types/staff-number/
+    pub type StaffNumber = u8;
+
+    /// The `staff-line` type indicates the line on a given staff.
+    /// Staff lines are numbered from bottom to top, with 1 being the bottom line on a staff.
+    ///
+    /// Spec: https://www.w3.org/2021/06/musicxml40/musicxml-reference/data-types/staff-line/
+    pub type StaffLine = i32;
+
     /// The `number-of-lines` type is used to specify the number of lines in text decoration attributes (0..3).
     ///
     /// Spec: https://www.w3.org/2021/06/musicxml40/musicxml-reference/data-types/number-of-lines/
@@ -437,6 +2144,64 @@ mod primitive {
     /// Spec: https://www.w3.org/2021/06/musicxml40/musicxml-reference/data-types/octave/
     pub type Octave = u8;
 
+    /// The `note-type-value` type is used for the MusicXML type element and represents the
+    /// graphic note type, from 1024th (shortest) to maxima (longest).
+    ///
+    /// Spec: https://www.w3.org/2021/06/musicxml40/musicxml-reference/data-types/note-type-value/
+    #[derive(Debug, Clone, Copy, Eq, Ord, Hash, PartialEq, PartialOrd, Deserialize, Serialize)]
+    pub enum NoteTypeValue {
+        #[serde(rename = "1024th")]
+        N1024th,
+        #[serde(rename = "512th")]
+        N512th,
+        #[serde(rename = "256th")]
+        N256th,
+        #[serde(rename = "128th")]
+        N128th,
+        #[serde(rename = "64th")]
+        N64th,
+        #[serde(rename = "32nd")]
+        N32nd,
+        #[serde(rename = "16th")]
+        N16th,
+        #[serde(rename = "eighth")]
+        Eighth,
+        #[serde(rename = "quarter")]
+        Quarter,
+        #[serde(rename = "half")]
+        Half,
+        #[serde(rename = "whole")]
+        Whole,
+        #[serde(rename = "breve")]
+        Breve,
+        #[serde(rename = "long")]
+        Long,
+        #[serde(rename = "maxima")]
+        Maxima,
+    }
+
+    impl NoteTypeValue {
+        /// Length of an undotted note of this type, in quarter notes.
+        pub fn quarters(self) -> f64 {
+            match self {
+                NoteTypeValue::N1024th => 1.0 / 256.0,
+                NoteTypeValue::N512th => 1.0 / 128.0,
+                NoteTypeValue::N256th => 1.0 / 64.0,
+                NoteTypeValue::N128th => 1.0 / 32.0,
+                NoteTypeValue::N64th => 1.0 / 16.0,
+                NoteTypeValue::N32nd => 1.0 / 8.0,
+                NoteTypeValue::N16th => 1.0 / 4.0,
+                NoteTypeValue::Eighth => 1.0 / 2.0,
+                NoteTypeValue::Quarter => 1.0,
+                NoteTypeValue::Half => 2.0,
+                NoteTypeValue::Whole => 4.0,
+                NoteTypeValue::Breve => 8.0,
+                NoteTypeValue::Long => 16.0,
+                NoteTypeValue::Maxima => 32.0,
+            }
+        }
+    }
+
     /// The step type represents a step of the diatonic scale, represented using the English letters A through G.
     ///
     /// Spec: https://www.w3.org/2021/06/musicxml40/musicxml-reference/data-types/step/
@@ -450,4 +2215,43 @@ mod primitive {
         F,
         G,
     }
+
+    impl Step {
+        /// Steps in scale order, starting at C.
+        pub const ALL: [Step; 7] = [
+            Step::C,
+            Step::D,
+            Step::E,
+            Step::F,
+            Step::G,
+            Step::A,
+            Step::B,
+        ];
+
+        /// Position of the step within an octave, C being 0 and B being 6.
+        pub fn index(self) -> u8 {
+            match self {
+                Step::C => 0,
+                Step::D => 1,
+                Step::E => 2,
+                Step::F => 3,
+                Step::G => 4,
+                Step::A => 5,
+                Step::B => 6,
+            }
+        }
+
+        /// Semitones from C up to the step.
+        pub fn semitone(self) -> u8 {
+            match self {
+                Step::C => 0,
+                Step::D => 2,
+                Step::E => 4,
+                Step::F => 5,
+                Step::G => 7,
+                Step::A => 9,
+                Step::B => 11,
+            }
+        }
+    }
 }
@@ -0,0 +1,53 @@
+//! Unpitched percussion parts, played on the General MIDI percussion channel.
+
+use crate::{
+    midi_note_number,
+    musicxml::{MeasureItem, Note, Part, ScorePart, Unpitched},
+};
+
+/// Drum key used when neither the instrument nor the note tell us what to play.
+const ACOUSTIC_SNARE: u8 = 38;
+
+/// A part is played as percussion if it asks for MIDI channel 10, maps any of its instruments
+/// to a drum key, or contains unpitched notes.
+pub fn is_percussion(score_part: Option<&ScorePart>, part: &Part) -> bool {
+    let midi = score_part
+        .into_iter()
+        .flat_map(|score_part| &score_part.midi_instrument)
+        .any(|instrument| {
+            instrument.midi_channel == Some(10) || instrument.midi_unpitched.is_some()
+        });
+
+    midi || part
+        .measure
+        .iter()
+        .flat_map(|measure| &measure.content)
+        .any(|item| matches!(item, MeasureItem::Note(note) if note.unpitched.is_some()))
+}
+
+/// General MIDI drum key of an unpitched note.
+///
+/// The `<midi-unpitched>` of the note's `<instrument>` wins, followed by the first one of the
+/// part. Without any, the displayed position on the staff is played as if it was a pitch.
+pub fn drum_key(note: &Note, unpitched: &Unpitched, score_part: Option<&ScorePart>) -> u8 {
+    let mut instruments = score_part
+        .into_iter()
+        .flat_map(|score_part| &score_part.midi_instrument);
+
+    let midi_unpitched = match note.instrument.first() {
+        Some(instrument) => instruments
+            .filter(|midi| midi.id == instrument.id)
+            .find_map(|midi| midi.midi_unpitched),
+        None => instruments.find_map(|midi| midi.midi_unpitched),
+    };
+
+    if let Some(key) = midi_unpitched {
+        // `midi-unpitched` counts from 1
+        return key.saturating_sub(1);
+    }
+
+    match (unpitched.display_step, unpitched.display_octave) {
+        (Some(step), Some(octave)) => midi_note_number(step, octave, 0.0),
+        _ => ACOUSTIC_SNARE,
+    }
+}
@@ -0,0 +1,254 @@
+//! Playback changes made by `<sound>`: instrument switches, pizzicato, mutes, pedals, panning
+//! and dynamics.
+
+use crate::{
+    musicxml::{MidiInstrument, Mute, RotationDegrees, ScorePart, Sound},
+    timeline::{Control, ControlChange, TimelineEvent},
+};
+
+/// Velocity of notes played before any `<sound dynamics>`.
+const DEFAULT_VELOCITY: u8 = 127;
+
+/// Velocity of the forte `<sound dynamics>` is a percentage of.
+const FORTE_VELOCITY: f64 = 90.0;
+
+/// General MIDI programs, counting from 0.
+const PIZZICATO_STRINGS: u8 = 45;
+const MUTED_TRUMPET: u8 = 59;
+
+/// Expression of muted instruments without a General MIDI sound of their own.
+const MUTED_EXPRESSION: u8 = 80;
+
+const BANK_SELECT: u8 = 0;
+const VOLUME: u8 = 7;
+const PAN: u8 = 10;
+const EXPRESSION: u8 = 11;
+const BANK_SELECT_LSB: u8 = 32;
+const DAMPER_PEDAL: u8 = 64;
+const SOSTENUTO_PEDAL: u8 = 66;
+const SOFT_PEDAL: u8 = 67;
+
+/// Trumpet, trombone, tuba and french horn, played as a muted trumpet when muted.
+fn has_muted_sound(program: u8) -> bool {
+    matches!(program, 56..=58 | 60)
+}
+
+/// Controller value of a pan given in degrees, sounds behind the listener are mirrored to
+/// the front.
+fn pan_value(degrees: RotationDegrees) -> u8 {
+    let degrees = if degrees > 90.0 {
+        180.0 - degrees
+    } else if degrees < -90.0 {
+        -180.0 - degrees
+    } else {
+        degrees
+    };
+
+    ((degrees + 90.0) / 180.0 * 127.0).round().clamp(0.0, 127.0) as u8
+}
+
+/// Controller value of a pedal attribute: "yes", "no" or the depression in percent.
+fn pedal_value(pedal: &str) -> Option<u8> {
+    match pedal.trim() {
+        "yes" => Some(127),
+        "no" => Some(0),
+        percent => percent
+            .parse::<f64>()
+            .ok()
+            .map(|percent| (percent / 100.0 * 127.0).round().clamp(0.0, 127.0) as u8),
+    }
+}
+
+/// Playback state of a part.
+#[derive(Debug)]
+pub struct Playback<'a> {
+    /// Index of the part in `ScorePartwise::part`.
+    part: usize,
+    instruments: &'a [MidiInstrument],
+    /// Program of the current instrument, counting from 0.
+    program: Option<u8>,
+    /// Program the channel was last switched to.
+    sent: Option<u8>,
+    pizzicato: bool,
+    mute: Option<Mute>,
+    velocity: u8,
+}
+
+impl<'a> Playback<'a> {
+    /// Sets the part up for its first `<midi-instrument>`.
+    pub fn new(
+        events: &mut Vec<TimelineEvent>,
+        score_part: Option<&'a ScorePart>,
+        part: usize,
+    ) -> Self {
+        let instruments = score_part.map_or(&[][..], |part| &part.midi_instrument);
+
+        let mut playback = Self {
+            part,
+            instruments,
+            program: None,
+            sent: None,
+            pizzicato: false,
+            mute: None,
+            velocity: DEFAULT_VELOCITY,
+        };
+
+        if let Some(instrument) = instruments.first() {
+            playback.instrument(events, 0, instrument);
+        }
+
+        playback
+    }
+
+    /// Velocity of the notes played at the current dynamics.
+    pub fn velocity(&self) -> u8 {
+        self.velocity
+    }
+
+    pub fn sound(&mut self, events: &mut Vec<TimelineEvent>, position: usize, sound: &Sound) {
+        for change in sound.instrument_change.iter() {
+            let replaced = sound
+                .midi_instrument
+                .iter()
+                .any(|midi| midi.id == change.id);
+            let instrument = self.instruments.iter().find(|midi| midi.id == change.id);
+
+            if let (false, Some(instrument)) = (replaced, instrument) {
+                self.instrument(events, position, instrument);
+            }
+        }
+
+        for midi in sound.midi_instrument.iter() {
+            // Only what changes needs to be spelled out, the rest comes from the part list
+            let instrument = match self.instruments.iter().find(|base| base.id == midi.id) {
+                Some(base) => MidiInstrument {
+                    id: midi.id.clone(),
+                    midi_channel: midi.midi_channel.or(base.midi_channel),
+                    midi_name: midi.midi_name.clone().or(base.midi_name.clone()),
+                    midi_bank: midi.midi_bank.or(base.midi_bank),
+                    midi_program: midi.midi_program.or(base.midi_program),
+                    midi_unpitched: midi.midi_unpitched.or(base.midi_unpitched),
+                    volume: midi.volume.or(base.volume),
+                    pan: midi.pan.or(base.pan),
+                    elevation: midi.elevation.or(base.elevation),
+                },
+                None => midi.clone(),
+            };
+
+            self.instrument(events, position, &instrument);
+        }
+
+        match sound.pizzicato.as_deref() {
+            Some("yes") => self.pizzicato = true,
+            Some("no") => self.pizzicato = false,
+            _ => {}
+        }
+
+        if let Some(mute) = sound.play.iter().find_map(|play| play.mute) {
+            let mute = (mute != Mute::Off).then_some(mute);
+
+            if mute.is_some() != self.mute.is_some() && !self.program.is_some_and(has_muted_sound) {
+                let expression = if mute.is_some() {
+                    MUTED_EXPRESSION
+                } else {
+                    127
+                };
+                self.controller(events, position, EXPRESSION, expression);
+            }
+
+            self.mute = mute;
+        }
+
+        if let Some(dynamics) = sound.dynamics {
+            self.velocity = (dynamics / 100.0 * FORTE_VELOCITY)
+                .round()
+                .clamp(1.0, 127.0) as u8;
+        }
+
+        let pedals = [
+            (&sound.damper_pedal, DAMPER_PEDAL),
+            (&sound.soft_pedal, SOFT_PEDAL),
+            (&sound.sostenuto_pedal, SOSTENUTO_PEDAL),
+        ];
+
+        for (pedal, controller) in pedals {
+            if let Some(value) = pedal.as_deref().and_then(pedal_value) {
+                self.controller(events, position, controller, value);
+            }
+        }
+
+        if let Some(pan) = sound.pan {
+            self.controller(events, position, PAN, pan_value(pan));
+        }
+
+        self.program_change(events, position);
+    }
+
+    fn instrument(
+        &mut self,
+        events: &mut Vec<TimelineEvent>,
+        position: usize,
+        instrument: &MidiInstrument,
+    ) {
+        if let Some(bank) = instrument.midi_bank {
+            // `midi-bank` counts from 1
+            let bank = bank.saturating_sub(1);
+            self.controller(events, position, BANK_SELECT, (bank >> 7) as u8 & 0x7f);
+            self.controller(events, position, BANK_SELECT_LSB, bank as u8 & 0x7f);
+            // A new bank needs a new program change to take effect
+            self.sent = None;
+        }
+
+        if let Some(program) = instrument.midi_program {
+            // So does `midi-program`
+            self.program = Some(program.saturating_sub(1));
+        }
+
+        if let Some(volume) = instrument.volume {
+            let volume = (volume / 100.0 * 127.0).round().clamp(0.0, 127.0) as u8;
+            self.controller(events, position, VOLUME, volume);
+        }
+
+        if let Some(pan) = instrument.pan {
+            self.controller(events, position, PAN, pan_value(pan));
+        }
+
+        self.program_change(events, position);
+    }
+
+    /// Switches the part to the program the current instrument, pizzicato and mute call for.
+    fn program_change(&mut self, events: &mut Vec<TimelineEvent>, position: usize) {
+        let program = match self.program {
+            Some(program) if self.mute.is_some() && has_muted_sound(program) => Some(MUTED_TRUMPET),
+            _ if self.pizzicato => Some(PIZZICATO_STRINGS),
+            // Back from pizzicato, on the General MIDI default without a program of its own
+            None if self.sent.is_some() => Some(0),
+            program => program,
+        };
+
+        let Some(program) = program.filter(|program| Some(*program) != self.sent) else {
+            return;
+        };
+
+        self.sent = Some(program);
+        events.push(TimelineEvent::Control(ControlChange {
+            position,
+            part: self.part,
+            control: Control::Program(program),
+        }));
+    }
+
+    fn controller(
+        &self,
+        events: &mut Vec<TimelineEvent>,
+        position: usize,
+        controller: u8,
+        value: u8,
+    ) {
+        events.push(TimelineEvent::Control(ControlChange {
+            position,
+            part: self.part,
+            control: Control::Controller { controller, value },
+        }));
+    }
+}
@@ -0,0 +1,187 @@
+//! Order in which measures are played once repeats, volta brackets and jumps (da capo, dal
+//! segno, to coda, fine) are taken.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::musicxml::{
+    BackwardForward, Barline, Ending, MeasureItem, Part, Sound, StartStopDiscontinue,
+};
+
+impl Ending {
+    /// Whether the bracket is played on the `pass`th time through the repeat.
+    ///
+    /// Accepts lists ("1, 2") as well as ranges ("1-3").
+    pub fn includes(&self, pass: u32) -> bool {
+        self.number
+            .split([',', ' '])
+            .any(|number| match number.split_once('-') {
+                Some((from, to)) => match (from.trim().parse::<u32>(), to.trim().parse::<u32>()) {
+                    (Ok(from), Ok(to)) => (from..=to).contains(&pass),
+                    _ => false,
+                },
+                None => number.trim().parse() == Ok(pass),
+            })
+    }
+}
+
+fn barlines(part: &Part, index: usize) -> impl Iterator<Item = &Barline> {
+    part.measure[index]
+        .content
+        .iter()
+        .filter_map(|item| match item {
+            MeasureItem::Barline(barline) => Some(barline),
+            _ => None,
+        })
+}
+
+fn has_repeat(part: &Part, index: usize, direction: BackwardForward) -> Option<u32> {
+    barlines(part, index)
+        .filter_map(|barline| barline.repeat.as_ref())
+        .find(|repeat| repeat.direction == direction)
+        .map(|repeat| repeat.times.unwrap_or(2))
+}
+
+fn ending(part: &Part, index: usize, kind: StartStopDiscontinue) -> Option<&Ending> {
+    barlines(part, index)
+        .filter_map(|barline| barline.ending.as_ref())
+        .find(|ending| ending.kind == kind)
+}
+
+fn sounds(part: &Part, index: usize) -> impl Iterator<Item = &Sound> {
+    part.measure[index]
+        .content
+        .iter()
+        .filter_map(|item| match item {
+            MeasureItem::Sound(sound) => Some(sound),
+            MeasureItem::Direction(direction) => direction.sound.as_ref(),
+            _ => None,
+        })
+}
+
+/// First measure with a sound marked `value` by `mark`, like the segno of a dal segno.
+fn marked(part: &Part, value: &str, mark: impl Fn(&Sound) -> Option<&str>) -> Option<usize> {
+    (0..part.measure.len())
+        .find(|index| sounds(part, *index).any(|sound| mark(sound) == Some(value)))
+}
+
+/// Measure a da capo or dal segno at the end of the measure goes back to.
+fn jump_back(part: &Part, index: usize) -> Option<usize> {
+    sounds(part, index).find_map(|sound| {
+        if sound.dacapo.as_deref() == Some("yes") {
+            Some(0)
+        } else {
+            let segno = sound.dalsegno.as_deref()?;
+            marked(part, segno, |sound| sound.segno.as_deref())
+        }
+    })
+}
+
+/// Coda a to coda at the end of the measure skips to.
+fn to_coda(part: &Part, index: usize) -> Option<usize> {
+    sounds(part, index).find_map(|sound| {
+        let coda = sound.tocoda.as_deref()?;
+        marked(part, coda, |sound| sound.coda.as_deref())
+    })
+}
+
+/// Indices into `Part::measure` in the order they are played.
+///
+/// A backward repeat jumps back to the last forward repeat (or the beginning of the piece,
+/// or the end of the previous repeated section) until it was played `times` times. Volta
+/// brackets not meant for the current time through are skipped.
+///
+/// Jumps given by `<sound>` are taken at the end of their measure: a da capo goes back to the
+/// beginning and a dal segno to the measure with the matching `segno`, each of them once. Once
+/// back, repeats are no longer taken and only the last volta bracket is played, a to coda
+/// skips to the measure with the matching `coda` and the piece ends with the measure marked
+/// `fine`.
+pub fn playback_order(part: &Part) -> Vec<usize> {
+    let mut order = Vec::with_capacity(part.measure.len());
+    let mut taken: HashMap<usize, u32> = HashMap::new();
+    let mut jumps = HashSet::new();
+
+    let mut start = 0;
+    let mut pass = 1;
+    let mut jumped = false;
+    // Whether a da capo or dal segno was taken
+    let mut returned = false;
+    let mut index = 0;
+
+    while index < part.measure.len() {
+        if !jumped && has_repeat(part, index, BackwardForward::Forward).is_some() {
+            start = index;
+            pass = 1;
+        }
+        jumped = false;
+
+        let skipped = match ending(part, index, StartStopDiscontinue::Start) {
+            // The brackets leading back to a repeat were played already
+            Some(_) if returned => {
+                has_repeat(part, bracket_end(part, index), BackwardForward::Backward).is_some()
+            }
+            Some(ending) => !ending.includes(pass),
+            None => false,
+        };
+
+        if skipped {
+            // Skip to the measure after the end of the bracket
+            index = bracket_end(part, index) + 1;
+            continue;
+        }
+
+        order.push(index);
+
+        let repeat = has_repeat(part, index, BackwardForward::Backward).filter(|_| !returned);
+        if let Some(times) = repeat {
+            let count = taken.entry(index).or_insert(1);
+
+            if *count < times {
+                *count += 1;
+                pass += 1;
+                index = start;
+                jumped = true;
+                continue;
+            }
+
+            taken.remove(&index);
+            start = index + 1;
+            pass = 1;
+        } else if ending_ends(part, index) {
+            // The last bracket of a repeated section
+            start = index + 1;
+            pass = 1;
+        }
+
+        if returned && sounds(part, index).any(|sound| sound.fine.is_some()) {
+            break;
+        }
+
+        let back = jump_back(part, index).filter(|_| jumps.insert(index));
+        let coda = to_coda(part, index).filter(|coda| returned && *coda > index);
+
+        if let Some(target) = back.or(coda) {
+            returned = true;
+            start = target;
+            pass = 1;
+            jumped = true;
+            index = target;
+            continue;
+        }
+
+        index += 1;
+    }
+
+    order
+}
+
+/// Last measure of the volta bracket starting at `index`.
+fn bracket_end(part: &Part, index: usize) -> usize {
+    (index..part.measure.len())
+        .find(|end| ending_ends(part, *end))
+        .unwrap_or(index)
+}
+
+fn ending_ends(part: &Part, index: usize) -> bool {
+    ending(part, index, StartStopDiscontinue::Stop).is_some()
+        || ending(part, index, StartStopDiscontinue::Discontinue).is_some()
+}
@@ -0,0 +1,212 @@
+//! Where notes are written on the staff.
+//!
+//! `<pitch>` holds the pitch a note sounds at (transposing instruments aside), while the
+//! position of its note head also depends on the clef (including its octave change) and on
+//! any 8va/8vb/15ma line the note sits under.
+
+use std::collections::HashMap;
+
+use crate::musicxml::{
+    Clef, ClefSign, Direction, MeasureItem, Note, NumberLevel, Octave, Part, StaffLine,
+    StaffNumber, Step, UpDownStopContinue,
+};
+
+/// Vertical position of a note head, counted in staff steps (half the distance between two
+/// staff lines) from the bottom line of a five-line staff.
+///
+/// Even positions sit on a line and odd positions in a space, `8` is the top line.
+/// Positions below `0` or above `8` need ledger lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StaffPosition(pub i32);
+
+impl StaffPosition {
+    pub fn is_line(self) -> bool {
+        self.0.rem_euclid(2) == 0
+    }
+
+    /// The staff line the note head sits on, numbered like `StaffLine` (1 is the bottom line).
+    pub fn line(self) -> Option<StaffLine> {
+        self.is_line().then_some(self.0.div_euclid(2) + 1)
+    }
+
+    /// The space the note head sits in, 1 being the space above the bottom line.
+    pub fn space(self) -> Option<i32> {
+        (!self.is_line()).then_some(self.0.div_euclid(2) + 1)
+    }
+
+    /// Number of ledger lines the note head needs, negative below the staff and positive above.
+    pub fn ledger_lines(self) -> i32 {
+        if self.0 < 0 {
+            self.0 / 2
+        } else if self.0 > 8 {
+            (self.0 - 8) / 2
+        } else {
+            0
+        }
+    }
+}
+
+impl Clef {
+    /// Staff position of a note head written at `step`/`octave`.
+    ///
+    /// Returns `None` for clefs that do not map pitches onto staff lines (TAB, jianpu, none).
+    pub fn position(&self, step: Step, octave: Octave) -> Option<StaffPosition> {
+        // The pitch sitting on the clef's line
+        let (reference, default_line) = match self.sign {
+            ClefSign::G => ((Step::G, 4), 2),
+            ClefSign::F => ((Step::F, 3), 4),
+            ClefSign::C => ((Step::C, 4), 3),
+            // Unpitched display positions are read as if they were in treble clef
+            ClefSign::Percussion => ((Step::G, 4), 2),
+            ClefSign::Tab | ClefSign::Jianpu | ClefSign::None => return None,
+        };
+
+        let line = match self.sign {
+            ClefSign::Percussion => default_line,
+            _ => self.line.unwrap_or(default_line),
+        };
+
+        let octave_change = self.clef_octave_change.unwrap_or(0);
+        let reference = (reference.1 + octave_change) * 7 + reference.0.index() as i32;
+        let steps = octave as i32 * 7 + step.index() as i32 - reference;
+
+        Some(StaffPosition(2 * (line - 1) + steps))
+    }
+}
+
+/// Clef and octave-shift state of every staff of a part, advanced item by item.
+#[derive(Debug, Default)]
+pub struct Staves {
+    clefs: HashMap<StaffNumber, Clef>,
+    /// Active octave shifts of each staff, in staff steps, keyed by their `number`.
+    shifts: HashMap<StaffNumber, HashMap<NumberLevel, i32>>,
+}
+
+impl Staves {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clef(&self, staff: StaffNumber) -> Option<&Clef> {
+        self.clefs.get(&staff)
+    }
+
+    /// Staff steps the notes of `staff` are currently written away from their pitch.
+    pub fn shift(&self, staff: StaffNumber) -> i32 {
+        self.shifts
+            .get(&staff)
+            .map(|shifts| shifts.values().sum())
+            .unwrap_or(0)
+    }
+
+    pub fn update(&mut self, item: &MeasureItem) {
+        match item {
+            MeasureItem::Attributes(attributes) => {
+                for clef in attributes.clef.iter() {
+                    // Additional clefs are cue clefs, the staff keeps its main clef
+                    if clef.additional.as_deref() == Some("yes") {
+                        continue;
+                    }
+
+                    self.clefs.insert(clef.number.unwrap_or(1), clef.clone());
+                }
+            }
+            MeasureItem::Direction(direction) => self.direction(direction),
+            _ => {}
+        }
+    }
+
+    fn direction(&mut self, direction: &Direction) {
+        let staff = direction.staff.unwrap_or(1);
+
+        let shifts = direction
+            .direction_type
+            .iter()
+            .filter_map(|direction_type| direction_type.octave_shift.as_ref());
+
+        for shift in shifts {
+            let octaves = match shift.size.unwrap_or(8) {
+                15 => 2,
+                22 => 3,
+                _ => 1,
+            };
+
+            let number = shift.number.unwrap_or(1);
+            let active = self.shifts.entry(staff).or_default();
+
+            match shift.kind {
+                UpDownStopContinue::Up => {
+                    active.insert(number, 7 * octaves);
+                }
+                UpDownStopContinue::Down => {
+                    active.insert(number, -7 * octaves);
+                }
+                UpDownStopContinue::Stop => {
+                    active.remove(&number);
+                }
+                UpDownStopContinue::Continue => {}
+            }
+        }
+    }
+
+    /// Written position of a note, `None` for rests and notes on staves without a usable clef.
+    pub fn position(&self, note: &Note) -> Option<StaffPosition> {
+        let (step, octave) = match (&note.pitch, &note.unpitched) {
+            (Some(pitch), _) => (pitch.step, pitch.octave),
+            (None, Some(unpitched)) => (unpitched.display_step?, unpitched.display_octave?),
+            (None, None) => return None,
+        };
+
+        let staff = note.staff.unwrap_or(1);
+        let position = self.clef(staff)?.position(step, octave)?;
+
+        // Shifts apply to pitches, not to the display positions of unpitched notes
+        let shift = if note.pitch.is_some() {
+            self.shift(staff)
+        } else {
+            0
+        };
+
+        Some(StaffPosition(position.0 + shift))
+    }
+}
+
+/// A note of a part, along with where it is written.
+#[derive(Debug, Clone, Copy)]
+pub struct WrittenNote<'a> {
+    /// Index of the measure in `Part::measure`.
+    pub measure: usize,
+    /// Index of the note in `Measure::content`.
+    pub item: usize,
+    pub staff: StaffNumber,
+    pub position: StaffPosition,
+    pub note: &'a Note,
+}
+
+/// Written positions of every pitched or unpitched note of a part, in document order.
+pub fn written_notes(part: &Part) -> Vec<WrittenNote<'_>> {
+    let mut staves = Staves::new();
+    let mut notes = Vec::new();
+
+    for (measure_id, measure) in part.measure.iter().enumerate() {
+        for (item_id, item) in measure.content.iter().enumerate() {
+            staves.update(item);
+
+            let MeasureItem::Note(note) = item else {
+                continue;
+            };
+
+            if let Some(position) = staves.position(note) {
+                notes.push(WrittenNote {
+                    measure: measure_id,
+                    item: item_id,
+                    staff: note.staff.unwrap_or(1),
+                    position,
+                    note,
+                });
+            }
+        }
+    }
+
+    notes
+}
@@ -0,0 +1,51 @@
+//! Swing: pairs of eighth (or sixteenth) notes played long-short.
+
+use crate::{
+    TICKS_PER_QUARTER_NOTE_F64,
+    musicxml::{NoteTypeValue, Swing},
+};
+
+impl Swing {
+    /// Share of a pair taken by its first note, `None` when the notes are played straight.
+    pub fn ratio(&self) -> Option<f64> {
+        if self.straight.is_some() {
+            return None;
+        }
+
+        let first = self.first? as f64;
+        let second = self.second? as f64;
+
+        (first > 0.0 && second > 0.0 && first != second).then(|| first / (first + second))
+    }
+
+    /// Length of a pair of swung notes in ticks.
+    fn pair(&self) -> f64 {
+        let unit = self.swing_type.unwrap_or(NoteTypeValue::Eighth);
+        2.0 * unit.quarters() * TICKS_PER_QUARTER_NOTE_F64
+    }
+
+    /// Position a note written at `position` is played at, `measure` being the start of its
+    /// measure.
+    ///
+    /// Pairs start on the beat: the off-beat moves to its swung position and everything in
+    /// between is stretched or squeezed along, so on-beat notes stay where they are.
+    pub(crate) fn apply(&self, position: usize, measure: usize) -> usize {
+        let Some(ratio) = self.ratio() else {
+            return position;
+        };
+
+        let pair = self.pair();
+        let offset = position.saturating_sub(measure) as f64;
+        let within = offset % pair;
+        let half = pair / 2.0;
+        let off_beat = pair * ratio;
+
+        let swung = if within <= half {
+            within / half * off_beat
+        } else {
+            off_beat + (within - half) / half * (pair - off_beat)
+        };
+
+        (position as f64 - within + swung).round() as usize
+    }
+}
@@ -0,0 +1,586 @@
+//! Tempo of the score, from `<sound tempo>`, `<metronome>` marks and tempo words.
+
+use crate::{
+    MINUTE, Options, TICKS_PER_QUARTER_NOTE,
+    measures::{self, MeasureMap},
+    musicxml::{
+        Direction, MeasureItem, Metronome, MetronomeContent, NumberLevel, Part, ScorePartwise,
+        Sound, StartStopContinue, Time,
+    },
+    repeats, timeline,
+};
+
+/// Tempo assumed until the score says otherwise, in quarter notes per minute.
+pub const DEFAULT_TEMPO: f64 = 120.0;
+
+/// Distance in ticks between two tempo events of a gradual tempo change.
+const GRADUAL_STEP: usize = TICKS_PER_QUARTER_NOTE as usize / 2;
+
+/// Tempo reached by a ritardando or accelerando that is not followed by a new tempo,
+/// relative to the tempo it started from.
+const RITARDANDO_RATIO: f64 = 0.75;
+const ACCELERANDO_RATIO: f64 = 1.25;
+
+/// Conventional tempo of common tempo markings, in quarter notes per minute.
+///
+/// Markings are matched word by word, so "allegretto" is not read as "allegro".
+const TEMPO_WORDS: &[(&str, f64)] = &[
+    ("grave", 40.0),
+    ("larghetto", 63.0),
+    ("largo", 50.0),
+    ("lento", 52.0),
+    ("adagietto", 76.0),
+    ("adagio", 70.0),
+    ("andantino", 100.0),
+    ("andante", 92.0),
+    ("moderato", 112.0),
+    ("allegretto", 116.0),
+    ("allegro", 132.0),
+    ("vivacissimo", 172.0),
+    ("vivace", 160.0),
+    ("prestissimo", 200.0),
+    ("presto", 180.0),
+    ("slowly", 60.0),
+    ("slow", 60.0),
+    ("moderately", 108.0),
+    ("moderate", 108.0),
+    ("fast", 160.0),
+];
+
+/// Length of a dotted note, `quarters` being the length of the undotted one.
+fn dotted(quarters: f64, dots: usize) -> f64 {
+    quarters * (2.0 - 0.5f64.powi(dots as i32))
+}
+
+impl Metronome {
+    /// Length of every beat unit of the mark in quarter notes: one for `beat-unit = per-minute`
+    /// and two for a metric modulation. Tied beat units add up to a single length.
+    pub fn beat_units(&self) -> Vec<f64> {
+        let mut units: Vec<(f64, usize)> = Vec::new();
+
+        for item in self.content.iter() {
+            match item {
+                MetronomeContent::BeatUnit(unit) => units.push((unit.value.quarters(), 0)),
+                MetronomeContent::BeatUnitDot => {
+                    if let Some((_, dots)) = units.last_mut() {
+                        *dots += 1;
+                    }
+                }
+                MetronomeContent::BeatUnitTied(tied) => {
+                    if let Some((quarters, dots)) = units.last_mut() {
+                        *quarters = dotted(*quarters, *dots)
+                            + dotted(tied.beat_unit.value.quarters(), tied.beat_unit_dot.len());
+                        *dots = 0;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        units
+            .into_iter()
+            .map(|(quarters, dots)| dotted(quarters, dots))
+            .collect()
+    }
+
+    /// The beats per minute of the mark, taking the first number of values like "ca. 60"
+    /// or "60-66".
+    pub fn per_minute(&self) -> Option<f64> {
+        self.content.iter().find_map(|item| match item {
+            MetronomeContent::PerMinute(text) => first_number(text),
+            _ => None,
+        })
+    }
+
+    /// Tempo in quarter notes per minute after this mark, `current` being the tempo before it
+    /// (a metric modulation only relates the new tempo to the previous one).
+    pub fn quarter_tempo(&self, current: f64) -> Option<f64> {
+        match self.beat_units().as_slice() {
+            [unit] => self.per_minute().map(|per_minute| per_minute * unit),
+            // The new unit lasts as long as the old one did
+            [old, new] => Some(current / old * new),
+            _ => None,
+        }
+    }
+}
+
+fn first_number(text: &str) -> Option<f64> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let text = &text[start..];
+    let end = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+
+    text[..end].trim_end_matches('.').parse().ok()
+}
+
+/// Conventional tempo of a tempo marking like "Allegro ma non troppo", in quarter notes
+/// per minute.
+pub fn tempo_from_words(text: &str) -> Option<f64> {
+    let text = text.to_lowercase();
+
+    text.split(|c: char| !c.is_alphabetic()).find_map(|word| {
+        TEMPO_WORDS
+            .iter()
+            .find(|(name, _)| *name == word)
+            .map(|(_, tempo)| *tempo)
+    })
+}
+
+/// Tempo set by `<sound tempo>`, in quarter notes per minute.
+pub fn sound_tempo(sound: &Sound) -> Option<f64> {
+    sound
+        .tempo
+        .as_ref()
+        .and_then(|tempo| tempo.trim().parse::<f64>().ok())
+        .filter(|tempo| *tempo > 0.0)
+}
+
+/// Tempo set by a direction, in quarter notes per minute.
+///
+/// `<sound tempo>` is authoritative, followed by metronome marks and, when `words` is set,
+/// tempo markings. `current` is the tempo in effect before the direction.
+pub fn direction_tempo(direction: &Direction, current: f64, words: bool) -> Option<f64> {
+    if let Some(tempo) = direction.sound.as_ref().and_then(sound_tempo) {
+        return Some(tempo);
+    }
+
+    let metronome = direction
+        .direction_type
+        .iter()
+        .filter_map(|direction_type| direction_type.metronome.as_ref())
+        .find_map(|metronome| metronome.quarter_tempo(current));
+
+    if metronome.is_some() || !words {
+        return metronome;
+    }
+
+    direction
+        .direction_type
+        .iter()
+        .flat_map(|direction_type| &direction_type.words)
+        .find_map(|words| tempo_from_words(&words.text))
+}
+
+/// Direction of a gradual tempo change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gradual {
+    /// Ritardando, rallentando, allargando.
+    Slower,
+    /// Accelerando, stringendo.
+    Faster,
+}
+
+impl Gradual {
+    /// Recognizes gradual tempo change markings like "rit.", "rall." or "accel.".
+    pub fn from_words(text: &str) -> Option<Self> {
+        let text = text.to_lowercase();
+
+        text.split(|c: char| !c.is_alphabetic())
+            .find_map(|word| match word {
+                "rit" | "rall" => Some(Gradual::Slower),
+                "accel" | "string" => Some(Gradual::Faster),
+                _ if word.starts_with("ritard")
+                    || word.starts_with("rallent")
+                    || word.starts_with("allarg") =>
+                {
+                    Some(Gradual::Slower)
+                }
+                _ if word.starts_with("accelera") || word.starts_with("stringen") => {
+                    Some(Gradual::Faster)
+                }
+                _ => None,
+            })
+    }
+
+    fn ratio(self) -> f64 {
+        match self {
+            Gradual::Slower => RITARDANDO_RATIO,
+            Gradual::Faster => ACCELERANDO_RATIO,
+        }
+    }
+}
+
+/// Whether the text returns to the tempo from before a gradual change ("a tempo"),
+/// or to the first tempo of the piece ("tempo primo").
+fn resumes(text: &str) -> Option<bool> {
+    let text = text.to_lowercase();
+
+    if text.contains("tempo primo") || text.trim_end_matches(['.', ' ']).ends_with("tempo i") {
+        Some(true)
+    } else if text.contains("a tempo") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug)]
+struct Change {
+    position: usize,
+    tempo: f64,
+    /// Set by "a tempo", which never is the target of a gradual change.
+    resume: bool,
+}
+
+#[derive(Debug)]
+struct Span {
+    start: usize,
+    end: Option<usize>,
+    gradual: Gradual,
+    dashes: Option<NumberLevel>,
+}
+
+/// Tempo changes of a single part, collected direction by direction.
+///
+/// Gradual changes are spread over their span once the whole part is known: a span lasts
+/// until the end of its dashes, or until the next tempo change, and heads towards the tempo
+/// set at its end.
+#[derive(Debug)]
+pub struct TempoChanges {
+    words: bool,
+    current: f64,
+    first: Option<f64>,
+    before_gradual: Option<f64>,
+    changes: Vec<Change>,
+    spans: Vec<Span>,
+}
+
+impl TempoChanges {
+    /// `words` enables the tempo-word fallback of [`direction_tempo`].
+    pub fn new(words: bool) -> Self {
+        Self {
+            words,
+            current: DEFAULT_TEMPO,
+            first: None,
+            before_gradual: None,
+            changes: Vec::new(),
+            spans: Vec::new(),
+        }
+    }
+
+    fn set(&mut self, position: usize, tempo: f64, resume: bool) {
+        self.current = tempo;
+        self.first.get_or_insert(tempo);
+        self.changes.push(Change {
+            position,
+            tempo,
+            resume,
+        });
+    }
+
+    /// A direction met at `position`, its `<sound>` taking effect at `sound_position`.
+    pub fn direction(&mut self, position: usize, sound_position: usize, direction: &Direction) {
+        let words = || {
+            direction
+                .direction_type
+                .iter()
+                .flat_map(|direction_type| &direction_type.words)
+                .map(|words| words.text.as_str())
+        };
+
+        if let Some(tempo) = direction_tempo(direction, self.current, self.words) {
+            let sounding = direction.sound.as_ref().and_then(sound_tempo).is_some();
+            let position = if sounding { sound_position } else { position };
+            self.set(position, tempo, false);
+        } else if let Some(primo) = words().find_map(resumes) {
+            let tempo = if primo {
+                self.first
+            } else {
+                self.before_gradual
+            };
+
+            if let Some(tempo) = tempo {
+                self.set(position, tempo, true);
+            }
+        }
+
+        let dashes = direction
+            .direction_type
+            .iter()
+            .filter_map(|direction_type| direction_type.dashes.as_ref());
+
+        if let Some(gradual) = words().find_map(Gradual::from_words) {
+            let dashes = dashes
+                .clone()
+                .find(|dashes| dashes.kind == StartStopContinue::Start)
+                .map(|dashes| dashes.number.unwrap_or(1));
+
+            self.before_gradual = Some(self.current);
+            self.spans.push(Span {
+                start: position,
+                end: None,
+                gradual,
+                dashes,
+            });
+        }
+
+        for stop in dashes.filter(|dashes| dashes.kind == StartStopContinue::Stop) {
+            let number = Some(stop.number.unwrap_or(1));
+            let span = self
+                .spans
+                .iter_mut()
+                .rev()
+                .find(|span| span.end.is_none() && span.dashes == number);
+
+            if let Some(span) = span {
+                span.end = Some(position);
+            }
+        }
+    }
+
+    /// A `<sound>` standing on its own in the measure.
+    pub fn sound(&mut self, position: usize, sound: &Sound) {
+        if let Some(tempo) = sound_tempo(sound) {
+            self.set(position, tempo, false);
+        }
+    }
+
+    /// All tempo events of the part in position order, `end` being the end of the part.
+    pub fn resolve(mut self, end: usize) -> Vec<(usize, f64)> {
+        self.changes.sort_by_key(|change| change.position);
+
+        let mut events: Vec<(usize, f64)> = self
+            .changes
+            .iter()
+            .map(|change| (change.position, change.tempo))
+            .collect();
+
+        for span in self.spans.iter() {
+            let from = self
+                .changes
+                .iter()
+                .rev()
+                .find(|change| change.position <= span.start)
+                .map_or(DEFAULT_TEMPO, |change| change.tempo);
+
+            let next = self
+                .changes
+                .iter()
+                .find(|change| change.position > span.start);
+            let end = span
+                .end
+                .or(next.map(|change| change.position))
+                .unwrap_or(end);
+
+            let target = match next {
+                Some(change) if !change.resume && change.position == end => change.tempo,
+                _ => {
+                    let target = from * span.gradual.ratio();
+                    // A tempo change right at the end of the span takes over from there
+                    if next.is_none_or(|change| change.position != end) {
+                        events.push((end, target));
+                    }
+                    target
+                }
+            };
+
+            let length = end.saturating_sub(span.start);
+            let steps = (span.start..end).step_by(GRADUAL_STEP).skip(1);
+            for position in steps {
+                let progress = (position - span.start) as f64 / length as f64;
+                events.push((position, from + (target - from) * progress));
+            }
+        }
+
+        events.sort_by_key(|(position, _)| *position);
+        events
+    }
+}
+
+/// MIDI tempo of `tempo` quarter notes per minute.
+fn microseconds_per_quarter(tempo: f64) -> u32 {
+    (MINUTE.as_micros() as f64 / tempo).round() as u32
+}
+
+/// A tempo change of a [`TempoMap`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Segment {
+    tick: usize,
+    /// Tempo from `tick` on, as written to the MIDI file.
+    microseconds_per_quarter: u32,
+    /// Time elapsed from the beginning of the piece to `tick`.
+    seconds: f64,
+}
+
+impl Segment {
+    fn seconds_per_tick(&self) -> f64 {
+        self.microseconds_per_quarter as f64 / 1_000_000.0 / TICKS_PER_QUARTER_NOTE as f64
+    }
+}
+
+/// Tempo changes of the whole score, converting between ticks and seconds in O(log n).
+///
+/// Tempi are kept in whole microseconds per quarter note like in a MIDI file, so positions in
+/// seconds match the playback of the file exactly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TempoMap {
+    segments: Vec<Segment>,
+    /// The piece starts at [`DEFAULT_TEMPO`] since the score does not say otherwise.
+    implicit_start: bool,
+}
+
+impl Default for TempoMap {
+    fn default() -> Self {
+        Self::new([])
+    }
+}
+
+impl TempoMap {
+    /// `changes` are tempo changes in quarter notes per minute, of one or more parts. Where
+    /// several fall on the same tick, the first one wins.
+    pub fn new(changes: impl IntoIterator<Item = (usize, f64)>) -> Self {
+        let mut changes: Vec<(usize, f64)> = changes.into_iter().collect();
+        changes.sort_by_key(|(tick, _)| *tick);
+        changes.dedup_by_key(|(tick, _)| *tick);
+
+        let implicit_start = changes.first().is_none_or(|(tick, _)| *tick != 0);
+
+        let mut segments = vec![Segment {
+            tick: 0,
+            microseconds_per_quarter: microseconds_per_quarter(DEFAULT_TEMPO),
+            seconds: 0.0,
+        }];
+
+        for (tick, tempo) in changes {
+            let last = segments.last_mut().unwrap();
+            let microseconds_per_quarter = microseconds_per_quarter(tempo);
+
+            if last.tick == tick {
+                last.microseconds_per_quarter = microseconds_per_quarter;
+                continue;
+            }
+
+            let seconds = last.seconds + (tick - last.tick) as f64 * last.seconds_per_tick();
+            segments.push(Segment {
+                tick,
+                microseconds_per_quarter,
+                seconds,
+            });
+        }
+
+        Self {
+            segments,
+            implicit_start,
+        }
+    }
+
+    /// Tempo changes of every part of the score, every part being played in the measure order
+    /// of the first one.
+    pub fn from_score(score: &ScorePartwise, options: &Options) -> Self {
+        let order = score
+            .part
+            .first()
+            .map(repeats::playback_order)
+            .unwrap_or_default();
+
+        Self::new(
+            score
+                .part
+                .iter()
+                .flat_map(|part| part_tempi(part, &order, options.tempo_words)),
+        )
+    }
+
+    fn segment_at_tick(&self, tick: usize) -> &Segment {
+        let index = self
+            .segments
+            .partition_point(|segment| segment.tick <= tick);
+        &self.segments[index.saturating_sub(1)]
+    }
+
+    /// Tempo changes of the score as ticks and microseconds per quarter note.
+    pub fn changes(&self) -> impl Iterator<Item = (usize, u32)> + '_ {
+        self.segments
+            .iter()
+            .skip(usize::from(self.implicit_start))
+            .map(|segment| (segment.tick, segment.microseconds_per_quarter))
+    }
+
+    /// Tempo at `tick`, in quarter notes per minute.
+    pub fn tempo(&self, tick: usize) -> f64 {
+        MINUTE.as_micros() as f64 / self.segment_at_tick(tick).microseconds_per_quarter as f64
+    }
+
+    /// Seconds from the beginning of the piece to `tick`.
+    pub fn seconds(&self, tick: usize) -> f64 {
+        let segment = self.segment_at_tick(tick);
+        segment.seconds + (tick - segment.tick) as f64 * segment.seconds_per_tick()
+    }
+
+    /// The tick sounding `seconds` after the beginning of the piece, rounded to the nearest one.
+    pub fn tick(&self, seconds: f64) -> usize {
+        let index = self
+            .segments
+            .partition_point(|segment| segment.seconds <= seconds);
+        let segment = &self.segments[index.saturating_sub(1)];
+
+        let ticks = (seconds - segment.seconds).max(0.0) / segment.seconds_per_tick();
+        segment.tick + ticks.round() as usize
+    }
+}
+
+/// Tempo changes of a part played in measure `order`, walking its measures the way the
+/// timeline does without playing any note.
+fn part_tempi(part: &Part, order: &[usize], words: bool) -> Vec<(usize, f64)> {
+    let mut tempi = TempoChanges::new(words);
+    let map = MeasureMap::new(part);
+
+    let mut divisions = 1.0;
+    let mut measure_length: Option<usize> = None;
+    let mut next_measure = 0;
+    let mut end = 0;
+
+    for &index in order {
+        let (Some(measure), Some(span)) = (part.measure.get(index), map.get(index)) else {
+            continue;
+        };
+
+        let mut position = next_measure;
+        next_measure += span.length;
+
+        for item in measure.content.iter() {
+            match item {
+                MeasureItem::Attributes(attributes) => {
+                    if let Some(d) = attributes.divisions.as_ref() {
+                        divisions = d.trim().parse().unwrap();
+                    }
+
+                    if let Some(ticks) = attributes.time.first().and_then(Time::ticks) {
+                        measure_length = Some(ticks);
+                    }
+                }
+                MeasureItem::Note(note) if note.chord.is_none() => {
+                    let ticks = measures::note_ticks(note, divisions, measure_length);
+                    position = position.saturating_add(ticks);
+                }
+                MeasureItem::Backup(backup) => {
+                    let ticks = measures::duration_ticks(&backup.duration, divisions);
+                    position = position.saturating_sub(ticks);
+                }
+                MeasureItem::Forward(forward) => {
+                    position += measures::duration_ticks(&forward.duration, divisions);
+                }
+                MeasureItem::Direction(direction) => {
+                    let at =
+                        timeline::offset_position(position, direction.offset.as_ref(), divisions);
+                    let sound_at = direction.sound.as_ref().map_or(position, |sound| {
+                        let offset = timeline::sound_offset(direction, sound);
+                        timeline::offset_position(position, offset, divisions)
+                    });
+
+                    tempi.direction(at, sound_at, direction);
+                }
+                MeasureItem::Sound(sound) => {
+                    let at = timeline::offset_position(position, sound.offset.as_ref(), divisions);
+                    tempi.sound(at, sound);
+                }
+                _ => {}
+            }
+
+            end = end.max(position);
+        }
+    }
+
+    tempi.resolve(end)
+}
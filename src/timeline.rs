@@ -0,0 +1,738 @@
+//! Playback timeline of a score: the notes to play and the control changes in between, in
+//! score terms rather than MIDI ones.
+//!
+//! Repeats are taken, ties joined, and transposition, swing, glissandi and `<sound>` changes
+//! applied. Every note keeps track of the element it comes from.
+
+use std::{collections::HashMap, ops::Range};
+
+use crate::{
+    Options, TICKS_PER_QUARTER_NOTE_F64,
+    comping::Comping,
+    continuo::Continuo,
+    glissando, measures,
+    musicxml::{
+        self, MeasureItem, Midi16, Note, Part, ScorePart, ScorePartwise, StaffNumber, SyllabicValue,
+    },
+    note_key, percussion, playback, repeats,
+    tempo::TempoMap,
+    transposed_key,
+};
+
+/// A part of the score, as far as playback is concerned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelinePart {
+    /// The `id` of the part in the score.
+    pub id: String,
+    pub name: Option<String>,
+    /// Played by unpitched percussion, `key`s are General MIDI drum keys.
+    pub percussion: bool,
+    /// The `<midi-channel>` asked for by the part, counting from 1.
+    pub midi_channel: Option<Midi16>,
+    /// Number of staves, 2 for a grand staff.
+    pub staves: StaffNumber,
+    /// Voices of the part in numerical order.
+    pub voices: Vec<String>,
+}
+
+/// Identifies a `<note>` of the score.
+///
+/// Notes of repeated measures, glissando runs and octave doublings share the id of the note
+/// they are played for. Block chords accompanying chord symbols point to their `<harmony>` and
+/// continuo chords to the bass note they are played over, with `part` being the index of the
+/// generated part in `Timeline::parts`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NoteId {
+    /// Index of the part in `ScorePartwise::part`.
+    pub part: usize,
+    /// Index of the measure in `Part::measure`.
+    pub measure: usize,
+    /// Index of the note in `Measure::content`.
+    pub item: usize,
+}
+
+impl NoteId {
+    pub fn note<'a>(&self, score: &'a ScorePartwise) -> Option<&'a Note> {
+        let item = score
+            .part
+            .get(self.part)?
+            .measure
+            .get(self.measure)?
+            .content
+            .get(self.item)?;
+
+        match item {
+            MeasureItem::Note(note) => Some(note),
+            _ => None,
+        }
+    }
+}
+
+/// A sounding note.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineNote {
+    /// Start in ticks.
+    pub start: usize,
+    /// Length in ticks, tied notes included.
+    pub duration: usize,
+    /// MIDI key, at sounding pitch.
+    pub key: u8,
+    /// Semitones the pitch is away from `key`, for microtones.
+    pub detune: f64,
+    /// Pitch bend over the note as (tick, semitones away from `key`), for glissandi.
+    pub sweep: Vec<(usize, f64)>,
+    pub velocity: u8,
+    /// The `<note>` this note is played for.
+    pub id: NoteId,
+    pub staff: StaffNumber,
+    /// The `<voice>` of the note, "1" when the score does not say.
+    pub voice: String,
+    /// Fingers to play the note with, in score order.
+    pub fingering: Vec<Finger>,
+    /// What is sung on the note, one syllable per verse.
+    pub lyrics: Vec<Syllable>,
+}
+
+/// A `<lyric>` of a note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Syllable {
+    /// The `number` of the lyric, "1" when the score does not say.
+    pub verse: String,
+    /// Syllables sung on the same note are joined by a space.
+    pub text: String,
+    /// Where the (last) syllable sits in its word.
+    pub syllabic: SyllabicValue,
+    /// The syllable is held over the following notes.
+    pub extend: bool,
+}
+
+impl Syllable {
+    /// The syllable the way karaoke files show it: words end with a space, syllables that are
+    /// followed by more of the word with a hyphen.
+    pub fn karaoke(&self) -> String {
+        match self.syllabic {
+            SyllabicValue::Begin | SyllabicValue::Middle => format!("{}-", self.text),
+            SyllabicValue::Single | SyllabicValue::End => format!("{} ", self.text),
+        }
+    }
+}
+
+/// A `<fingering>` of a note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finger {
+    /// Usually 1 to 5, counting from the thumb.
+    pub finger: String,
+    /// Replaces the previous finger while the note is held.
+    pub substitution: bool,
+    /// May be used instead of the previous finger.
+    pub alternate: bool,
+}
+
+impl TimelineNote {
+    pub fn end(&self) -> usize {
+        self.start + self.duration
+    }
+}
+
+/// A change to how a part is played.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Control {
+    /// General MIDI program, counting from 0.
+    Program(u8),
+    Controller {
+        controller: u8,
+        value: u8,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ControlChange {
+    pub position: usize,
+    /// Index of the part in `ScorePartwise::part`.
+    pub part: usize,
+    pub control: Control,
+}
+
+/// Text shown at a point of the music.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    pub position: usize,
+    /// Index of the part in `ScorePartwise::part`.
+    pub part: usize,
+    pub kind: AnnotationKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationKind {
+    /// A place to jump to: a rehearsal mark, segno or coda.
+    Marker,
+    /// Words written over or under the staff.
+    Words,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimelineEvent {
+    Note(TimelineNote),
+    Control(ControlChange),
+    Annotation(Annotation),
+}
+
+/// Everything that is played, part after part.
+///
+/// Events of a part are in the order they are met in the score, not sorted by time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timeline {
+    pub parts: Vec<TimelinePart>,
+    pub events: Vec<TimelineEvent>,
+    pub tempo: TempoMap,
+}
+
+impl Timeline {
+    /// Every part is played in the measure order of the first one.
+    pub fn new(score: &ScorePartwise, options: &Options) -> Self {
+        let order = score
+            .part
+            .first()
+            .map(repeats::playback_order)
+            .unwrap_or_default();
+
+        let mut parts = Vec::with_capacity(score.part.len());
+        let mut events = Vec::new();
+
+        let mut generated = Generated::default();
+
+        // Chord symbols of the first part that has any, accompanied on a part of their own
+        let lead_sheet = score.part.iter().position(has_harmonies);
+        if let Some(source) = lead_sheet.filter(|_| options.comping) {
+            generated.comping = Some((source, Comping::new(score.part.len())));
+        }
+
+        let figured = score.part.iter().position(has_figured_bass);
+        if let Some(source) = figured.filter(|_| options.continuo) {
+            let part = score.part.len() + generated.comping.iter().count();
+            generated.continuo = Some((source, Continuo::new(part)));
+        }
+
+        for (id, part) in score.part.iter().enumerate() {
+            let score_part = score.part_list.score_part(&part.id);
+
+            parts.push(TimelinePart {
+                id: part.id.clone(),
+                name: score_part.map(|score_part| score_part.part_name.clone()),
+                percussion: percussion::is_percussion(score_part, part),
+                midi_channel: score_part
+                    .into_iter()
+                    .flat_map(|score_part| &score_part.midi_instrument)
+                    .find_map(|instrument| instrument.midi_channel),
+                staves: staves(part),
+                voices: voices(part),
+            });
+
+            parse_part(&mut events, id, score_part, part, &order, &mut generated);
+        }
+
+        let extra = [
+            generated.comping.is_some().then_some(("comping", "Chords")),
+            generated
+                .continuo
+                .is_some()
+                .then_some(("continuo", "Continuo")),
+        ];
+
+        for (id, name) in extra.into_iter().flatten() {
+            parts.push(TimelinePart {
+                id: id.to_string(),
+                name: Some(name.to_string()),
+                percussion: false,
+                midi_channel: None,
+                staves: 1,
+                voices: vec!["1".to_string()],
+            });
+        }
+
+        Self {
+            parts,
+            events,
+            tempo: TempoMap::from_score(score, options),
+        }
+    }
+
+    pub fn notes(&self) -> impl Iterator<Item = &TimelineNote> {
+        self.events.iter().filter_map(|event| match event {
+            TimelineEvent::Note(note) => Some(note),
+            TimelineEvent::Control(_) | TimelineEvent::Annotation(_) => None,
+        })
+    }
+
+    /// Notes of a single voice of the `part`th part.
+    pub fn voice<'a>(
+        &'a self,
+        part: usize,
+        voice: &'a str,
+    ) -> impl Iterator<Item = &'a TimelineNote> + 'a {
+        self.notes()
+            .filter(move |note| note.id.part == part && note.voice == voice)
+    }
+
+    pub fn index(&self) -> NoteIndex<'_> {
+        NoteIndex::new(self)
+    }
+}
+
+/// Lookup of the notes of a timeline by id and by time.
+#[derive(Debug, Clone)]
+pub struct NoteIndex<'a> {
+    /// Notes in start order.
+    notes: Vec<&'a TimelineNote>,
+    /// Latest end of the notes up to each one of `notes`.
+    ends: Vec<usize>,
+    ids: HashMap<NoteId, Vec<usize>>,
+}
+
+impl<'a> NoteIndex<'a> {
+    pub fn new(timeline: &'a Timeline) -> Self {
+        let mut notes: Vec<&TimelineNote> = timeline.notes().collect();
+        notes.sort_by_key(|note| note.start);
+
+        let ends = notes
+            .iter()
+            .scan(0, |end, note| {
+                *end = note.end().max(*end);
+                Some(*end)
+            })
+            .collect();
+
+        let mut ids: HashMap<NoteId, Vec<usize>> = HashMap::new();
+        for (index, note) in notes.iter().enumerate() {
+            ids.entry(note.id).or_default().push(index);
+        }
+
+        Self { notes, ends, ids }
+    }
+
+    /// Every note in start order.
+    pub fn notes(&self) -> &[&'a TimelineNote] {
+        &self.notes
+    }
+
+    /// Notes played for the `<note>` identified by `id`, in start order.
+    pub fn by_id(&self, id: NoteId) -> impl Iterator<Item = &'a TimelineNote> + '_ {
+        self.ids
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .map(|index| self.notes[*index])
+    }
+
+    /// Notes starting within `range` of ticks.
+    pub fn starting(&self, range: Range<usize>) -> &[&'a TimelineNote] {
+        let from = self.notes.partition_point(|note| note.start < range.start);
+        let to = self.notes.partition_point(|note| note.start < range.end);
+        &self.notes[from..to.max(from)]
+    }
+
+    /// Notes sounding at `tick`, in start order.
+    pub fn sounding(&self, tick: usize) -> Vec<&'a TimelineNote> {
+        let started = self.notes.partition_point(|note| note.start <= tick);
+
+        // Walk back as long as an earlier note may still be sounding
+        let first = self.ends[..started]
+            .iter()
+            .rposition(|end| *end <= tick)
+            .map_or(0, |index| index + 1);
+
+        self.notes[first..started]
+            .iter()
+            .filter(|note| note.end() > tick)
+            .copied()
+            .collect()
+    }
+}
+
+/// Number of staves of a part, from `<staves>` or the staves its notes are on.
+fn staves(part: &Part) -> StaffNumber {
+    part.measure
+        .iter()
+        .flat_map(|measure| &measure.content)
+        .filter_map(|item| match item {
+            MeasureItem::Attributes(attributes) => attributes.staves,
+            MeasureItem::Note(note) => note.staff,
+            _ => None,
+        })
+        .max()
+        .unwrap_or(1)
+}
+
+/// The `<voice>` of a note, "1" when the score does not say.
+pub fn voice(note: &Note) -> &str {
+    note.voice.as_deref().map_or("1", str::trim)
+}
+
+fn fingering(note: &Note) -> Vec<Finger> {
+    note.notations
+        .iter()
+        .flat_map(|notations| &notations.technical)
+        .flat_map(|technical| &technical.fingering)
+        .map(|fingering| Finger {
+            finger: fingering.finger.trim().to_string(),
+            substitution: fingering.substitution.as_deref() == Some("yes"),
+            alternate: fingering.alternate.as_deref() == Some("yes"),
+        })
+        .collect()
+}
+
+fn lyrics(note: &Note) -> Vec<Syllable> {
+    note.lyric
+        .iter()
+        .filter(|lyric| !lyric.text.is_empty())
+        .map(|lyric| {
+            let text: Vec<&str> = lyric.text.iter().map(|text| text.value.trim()).collect();
+
+            Syllable {
+                verse: lyric.number.as_deref().map_or("1", str::trim).to_string(),
+                text: text.join(" "),
+                syllabic: lyric
+                    .syllabic
+                    .last()
+                    .map(|syllabic| syllabic.value)
+                    .unwrap_or_default(),
+                extend: lyric.extend.is_some(),
+            }
+        })
+        .collect()
+}
+
+/// Parts played from what is written along the score rather than its notes, each with the
+/// index of the part they follow.
+#[derive(Debug, Default)]
+struct Generated<'a> {
+    comping: Option<(usize, Comping)>,
+    continuo: Option<(usize, Continuo<'a>)>,
+}
+
+impl<'a> Generated<'a> {
+    fn comping(&mut self, part: usize) -> Option<&mut Comping> {
+        self.comping
+            .as_mut()
+            .filter(|(source, _)| *source == part)
+            .map(|(_, comping)| comping)
+    }
+
+    fn continuo(&mut self, part: usize) -> Option<&mut Continuo<'a>> {
+        self.continuo
+            .as_mut()
+            .filter(|(source, _)| *source == part)
+            .map(|(_, continuo)| continuo)
+    }
+}
+
+fn has_harmonies(part: &Part) -> bool {
+    part.measure
+        .iter()
+        .flat_map(|measure| &measure.content)
+        .any(|item| matches!(item, MeasureItem::Harmony(_)))
+}
+
+fn has_figured_bass(part: &Part) -> bool {
+    part.measure
+        .iter()
+        .flat_map(|measure| &measure.content)
+        .any(|item| matches!(item, MeasureItem::FiguredBass(_)))
+}
+
+/// Voices of a part in numerical order, voices that are not numbers come last.
+fn voices(part: &Part) -> Vec<String> {
+    let mut voices: Vec<&str> = part
+        .measure
+        .iter()
+        .flat_map(|measure| &measure.content)
+        .filter_map(|item| match item {
+            MeasureItem::Note(note) => Some(voice(note)),
+            _ => None,
+        })
+        .collect();
+
+    voices.sort_by_key(|voice| (voice.parse::<u32>().unwrap_or(u32::MAX), *voice));
+    voices.dedup();
+    voices.into_iter().map(String::from).collect()
+}
+
+/// Appends the events of a part.
+fn parse_part<'a>(
+    events: &mut Vec<TimelineEvent>,
+    id: usize,
+    score_part: Option<&ScorePart>,
+    part: &'a Part,
+    order: &[usize],
+    generated: &mut Generated<'a>,
+) {
+    let mut divisions = 1.0;
+    let mut measure_length: Option<usize> = None;
+    let mut position: usize;
+    let mut chord_position = 0usize;
+    let mut transposes: &[musicxml::Transpose] = &[];
+    let mut glissandi = glissando::Glissandi::new(score_part);
+    let mut swing: Option<&musicxml::Swing> = None;
+    let mut playback = playback::Playback::new(events, score_part, id);
+
+    let map = measures::MeasureMap::new(part);
+
+    let mut next_measure = 0;
+
+    for &index in order {
+        let (Some(measure), Some(span)) = (part.measure.get(index), map.get(index)) else {
+            continue;
+        };
+
+        // Voices that stop short of the end of the measure do not delay the next one
+        let measure_start = next_measure;
+        next_measure += span.length;
+        position = measure_start;
+
+        for (item_id, item) in measure.content.iter().enumerate() {
+            match item {
+                MeasureItem::Attributes(attributes) => {
+                    if let Some(d) = attributes.divisions.as_ref() {
+                        divisions = d.trim().parse().unwrap();
+                    }
+
+                    if let Some(ticks) = attributes.time.first().and_then(musicxml::Time::ticks) {
+                        measure_length = Some(ticks);
+                    }
+
+                    if !attributes.transpose.is_empty() {
+                        transposes = &attributes.transpose;
+                    }
+
+                    if let (Some(key), Some(continuo)) =
+                        (attributes.key.first(), generated.continuo(id))
+                    {
+                        continuo.key(&key.fifths);
+                    }
+
+                    // assert_eq!(
+                    //     attributes.time,
+                    //     vec![musicxml::Time {
+                    //         beats: "4".into(),
+                    //         beat_type: "4".into(),
+                    //     }],
+                    // );
+                }
+                MeasureItem::Note(note) => {
+                    let ticks = measures::note_ticks(note, divisions, measure_length);
+
+                    // Chord notes start together with the note preceding them
+                    if note.chord.is_none() {
+                        chord_position = position;
+                        position = position.saturating_add(ticks);
+                    }
+
+                    let start = chord_position;
+                    let end = start + ticks;
+                    let (start, end) = match swing {
+                        Some(swing) => (
+                            swing.apply(start, measure_start),
+                            swing.apply(end, measure_start),
+                        ),
+                        None => (start, end),
+                    };
+
+                    let Some((pitch, detune)) = note_key(note, score_part) else {
+                        continue;
+                    };
+                    let staff = note.staff.unwrap_or(1);
+                    // Drum keys do not depend on how the staff is transposed
+                    let transpose = transposes
+                        .iter()
+                        .find(|t| t.applies_to(staff))
+                        .filter(|_| note.pitch.is_some());
+                    let pitch = transpose.map_or(pitch, |t| transposed_key(pitch, t));
+
+                    let timeline_note = TimelineNote {
+                        start,
+                        duration: end - start,
+                        key: pitch,
+                        detune,
+                        sweep: Vec::new(),
+                        velocity: playback.velocity(),
+                        id: NoteId {
+                            part: id,
+                            measure: index,
+                            item: item_id,
+                        },
+                        staff,
+                        voice: voice(note).to_string(),
+                        fingering: fingering(note),
+                        lyrics: lyrics(note),
+                    };
+
+                    if let (None, Some(continuo)) = (&note.chord, generated.continuo(id)) {
+                        continuo.note(events, note, &timeline_note);
+                    }
+
+                    glissandi.stop(events, note, pitch);
+                    if glissandi.start(note, timeline_note.clone()) {
+                        continue;
+                    }
+
+                    let double = transpose.and_then(|t| t.double.as_ref()).map(|double| {
+                        let octave = if double.above.as_deref() == Some("yes") {
+                            12
+                        } else {
+                            -12
+                        };
+                        (pitch as i32 + octave).clamp(0, 127) as u8
+                    });
+
+                    let tied = note
+                        .tie
+                        .iter()
+                        .any(|tie| tie.kind == musicxml::StartStop::Stop);
+
+                    for key in std::iter::once(pitch).chain(double) {
+                        if tied && extend_tie(events, &timeline_note, key) {
+                            continue;
+                        }
+
+                        events.push(TimelineEvent::Note(TimelineNote {
+                            key,
+                            ..timeline_note.clone()
+                        }));
+                    }
+                }
+                MeasureItem::Backup(backup) => {
+                    let ticks = measures::duration_ticks(&backup.duration, divisions);
+                    position = position.saturating_sub(ticks)
+                }
+                MeasureItem::Forward(forward) => {
+                    position += measures::duration_ticks(&forward.duration, divisions);
+                }
+                MeasureItem::FiguredBass(figured_bass) => {
+                    if let Some(continuo) = generated.continuo(id) {
+                        continuo.figured_bass(figured_bass, divisions);
+                    }
+                }
+                MeasureItem::Print(_) => {}
+                MeasureItem::Barline(_) => {}
+                MeasureItem::Direction(direction) => {
+                    let at = offset_position(position, direction.offset.as_ref(), divisions);
+                    annotate(events, id, at, direction);
+
+                    if let Some(sound) = direction.sound.as_ref() {
+                        let offset = sound_offset(direction, sound);
+                        let at = offset_position(position, offset, divisions);
+                        playback.sound(events, at, sound);
+                        swing = sound.swing.as_ref().or(swing);
+                    }
+                }
+                MeasureItem::Sound(sound) => {
+                    let at = offset_position(position, sound.offset.as_ref(), divisions);
+                    playback.sound(events, at, sound);
+                    swing = sound.swing.as_ref().or(swing);
+                }
+                MeasureItem::Harmony(harmony) => {
+                    if let Some(comping) = generated.comping(id) {
+                        let at = offset_position(position, harmony.offset.as_ref(), divisions);
+                        let id = NoteId {
+                            part: comping.part(),
+                            measure: index,
+                            item: item_id,
+                        };
+                        comping.harmony(events, at, harmony, id);
+                    }
+                }
+            }
+        }
+
+        if let Some(comping) = generated.comping(id) {
+            comping.measure_end(events, next_measure);
+        }
+    }
+
+    glissandi.finish(events);
+}
+
+/// Lengthens the note `note` is tied to, returns `false` if there is none.
+fn extend_tie(events: &mut [TimelineEvent], note: &TimelineNote, key: u8) -> bool {
+    let tied = events.iter_mut().rev().find_map(|event| match event {
+        TimelineEvent::Note(tied)
+            if tied.id.part == note.id.part
+                && tied.key == key
+                && tied.staff == note.staff
+                && tied.end() == note.start =>
+        {
+            Some(tied)
+        }
+        _ => None,
+    });
+
+    match tied {
+        Some(tied) => {
+            tied.duration += note.duration;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Rehearsal marks, segni, codas and words of a direction.
+fn annotate(
+    events: &mut Vec<TimelineEvent>,
+    part: usize,
+    position: usize,
+    direction: &musicxml::Direction,
+) {
+    for direction_type in direction.direction_type.iter() {
+        let markers = direction_type
+            .rehearsal
+            .iter()
+            .map(|rehearsal| rehearsal.text.trim().to_string())
+            .chain(direction_type.segno.iter().map(|_| "Segno".to_string()))
+            .chain(direction_type.coda.iter().map(|_| "Coda".to_string()))
+            .map(|text| (AnnotationKind::Marker, text));
+        let words = direction_type
+            .words
+            .iter()
+            .map(|words| (AnnotationKind::Words, words.text.trim().to_string()));
+
+        for (kind, text) in markers.chain(words) {
+            if text.is_empty() {
+                continue;
+            }
+
+            events.push(TimelineEvent::Annotation(Annotation {
+                position,
+                part,
+                kind,
+                text,
+            }));
+        }
+    }
+}
+
+/// The offset of the `<sound>` of a direction: its own, or the direction's when the latter
+/// says it moves the sound as well.
+pub(crate) fn sound_offset<'a>(
+    direction: &'a musicxml::Direction,
+    sound: &'a musicxml::Sound,
+) -> Option<&'a musicxml::Offset> {
+    sound.offset.as_ref().or(direction
+        .offset
+        .as_ref()
+        .filter(|offset| offset.sound.as_deref() == Some("yes")))
+}
+
+/// `position` moved by an `<offset>` given in divisions.
+pub(crate) fn offset_position(
+    position: usize,
+    offset: Option<&musicxml::Offset>,
+    divisions: f64,
+) -> usize {
+    let Some(offset) = offset else {
+        return position;
+    };
+
+    let ticks = (offset.value / divisions * TICKS_PER_QUARTER_NOTE_F64).round() as isize;
+    position.saturating_add_signed(ticks)
+}
@@ -0,0 +1,135 @@
+//! Transposing instruments: conversion from written to sounding (concert) pitch.
+
+use crate::musicxml::{
+    Defaults, Empty, Key, MeasureItem, Pitch, ScorePartwise, StaffNumber, Step, Transpose,
+};
+
+/// Diatonic steps of the most common spelling of an interval of `n` semitones (`n` in 0..12).
+const DIATONIC_STEPS: [i32; 12] = [0, 1, 1, 2, 2, 3, 3, 4, 5, 5, 6, 6];
+
+impl Transpose {
+    /// Semitones between the written and the sounding pitch.
+    pub fn semitones(&self) -> i32 {
+        self.chromatic.round() as i32 + 12 * self.octave_change.unwrap_or(0)
+    }
+
+    /// Diatonic steps between the written and the sounding pitch.
+    ///
+    /// When `<diatonic>` is missing, the most common spelling of the chromatic interval is used.
+    pub fn steps(&self) -> i32 {
+        let chromatic = self.chromatic.round() as i32;
+        let diatonic = self.diatonic.unwrap_or_else(|| {
+            let octaves = chromatic.div_euclid(12);
+            octaves * 7 + DIATONIC_STEPS[chromatic.rem_euclid(12) as usize]
+        });
+
+        diatonic + 7 * self.octave_change.unwrap_or(0)
+    }
+
+    /// Whether the notes of `staff` are transposed by `self`.
+    pub fn applies_to(&self, staff: StaffNumber) -> bool {
+        self.number.is_none_or(|number| number == staff)
+    }
+}
+
+impl Pitch {
+    /// The sounding pitch of a note written at `self` for an instrument transposed by `transpose`.
+    ///
+    /// The step moves by the diatonic interval, the alteration makes up for the rest, so a
+    /// written F♯ for a B♭ clarinet becomes a sounding E.
+    pub fn transposed(&self, transpose: &Transpose) -> Pitch {
+        let written = self.octave as i32 * 7 + self.step.index() as i32;
+        let sounding = written + transpose.steps();
+
+        let step = Step::ALL[sounding.rem_euclid(7) as usize];
+        let octave = sounding.div_euclid(7).clamp(0, 9);
+
+        let semitone = |step: Step, octave: i32| octave * 12 + step.semitone() as i32;
+
+        let alter = self.alter.unwrap_or(0.0)
+            + (semitone(self.step, self.octave as i32) + transpose.semitones()) as f64
+            - semitone(step, octave) as f64;
+
+        Pitch {
+            step,
+            alter: (alter != 0.0).then_some(alter),
+            octave: octave as u8,
+        }
+    }
+}
+
+impl Key {
+    /// Moves the key signature around the circle of fifths by the transposition interval.
+    fn transpose(&mut self, transpose: &Transpose) {
+        let Ok(fifths) = self.fifths.trim().parse::<i32>() else {
+            return;
+        };
+
+        // Every semitone up is 7 fifths up, modulo the octave
+        let mut fifths = fifths + (transpose.semitones() * 7).rem_euclid(12);
+        if fifths > 7 {
+            fifths -= 12;
+        }
+
+        self.fifths = fifths.to_string();
+    }
+}
+
+impl ScorePartwise {
+    /// A copy of the score with every transposing part written at concert pitch.
+    ///
+    /// Notes are transposed by the `<transpose>` of their staff, key signatures by the one of
+    /// the first staff, and unpitched notes are left alone. `<transpose>` elements are dropped,
+    /// except for those that double notes an octave apart, which keep their `<double>` only, and
+    /// `<concert-score>` is set.
+    pub fn concert_pitch(&self) -> ScorePartwise {
+        let mut score = self.clone();
+        score
+            .defaults
+            .get_or_insert_with(Defaults::default)
+            .concert_score = Some(Empty {});
+
+        for part in score.part.iter_mut() {
+            let mut transposes: Vec<Transpose> = Vec::new();
+
+            let items = part
+                .measure
+                .iter_mut()
+                .flat_map(|measure| measure.content.iter_mut());
+
+            for item in items {
+                match item {
+                    MeasureItem::Attributes(attributes) => {
+                        if !attributes.transpose.is_empty() {
+                            transposes = attributes.transpose.clone();
+                        }
+
+                        if let Some(transpose) = transposes.iter().find(|t| t.applies_to(1)) {
+                            for key in attributes.key.iter_mut() {
+                                key.transpose(transpose);
+                            }
+                        }
+
+                        attributes.transpose.retain(|t| t.double.is_some());
+                        for t in attributes.transpose.iter_mut() {
+                            t.diatonic = None;
+                            t.chromatic = 0.0;
+                            t.octave_change = None;
+                        }
+                    }
+                    MeasureItem::Note(note) => {
+                        let staff = note.staff.unwrap_or(1);
+                        let transpose = transposes.iter().find(|t| t.applies_to(staff));
+
+                        if let (Some(transpose), Some(pitch)) = (transpose, note.pitch.as_mut()) {
+                            *pitch = pitch.transposed(transpose);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        score
+    }
+}